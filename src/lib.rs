@@ -1,12 +1,16 @@
 use chrono::{DateTime, Local, Utc};
 use prometheus_parse::Value;
-use std::collections::{HashMap, BTreeMap};
+use serde::Serialize;
+use std::collections::{HashMap, BTreeMap, BTreeSet};
 //use serde_derive::{Serialize,Deserialize};
-use port_scanner::scan_port_addr;
-use std::process;
-use rayon;
 use std::sync::mpsc::channel;
-//use std::fs;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use std::{fmt, fs};
+use std::io::{BufRead, BufReader, Write};
+use flate2::Compression;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+use itertools::Itertools;
 //use regex::Regex;
 
 #[derive(Debug)]
@@ -19,6 +23,86 @@ pub struct NodeExporterValues {
     pub node_exporter_timestamp: DateTime<Utc>,
 }
 
+#[derive(Debug)]
+pub struct ScrapeStatistics {
+    pub duration: Duration,
+    pub size: usize,
+}
+
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Connection {
+    pub connect_timeout: u64,
+    pub read_timeout: u64,
+    pub ca_cert: Option<String>,
+    pub client_cert: Option<String>,
+    pub client_key: Option<String>,
+    pub insecure: bool,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub bearer_token: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct Target {
+    pub role: Option<String>,
+    pub scheme: String,
+    pub host: String,
+    pub port: String,
+    pub endpoint: Option<String>,
+    // set when the host answered the probe, but serves no prometheus endpoint, so that it is not probed again.
+    pub no_endpoint: bool,
+    pub connection: Connection,
+    pub client: reqwest::blocking::Client,
+}
+
+// the password and bearer token are not shown when a connection or target is printed for debugging.
+fn redacted(
+    secret: &Option<String>,
+) -> Option<&str> {
+    secret.as_ref().map(|_| "<redacted>")
+}
+
+impl fmt::Debug for Connection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Connection")
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("ca_cert", &self.ca_cert)
+            .field("client_cert", &self.client_cert)
+            .field("client_key", &self.client_key)
+            .field("insecure", &self.insecure)
+            .field("user", &self.user)
+            .field("password", &redacted(&self.password))
+            .field("bearer_token", &redacted(&self.bearer_token))
+            .finish()
+    }
+}
+
+impl fmt::Debug for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Target")
+            .field("role", &self.role)
+            .field("scheme", &self.scheme)
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("endpoint", &self.endpoint)
+            .field("no_endpoint", &self.no_endpoint)
+            .field("connection", &self.connection)
+            .finish_non_exhaustive()
+    }
+}
+
+// the roles that can be given for a target, with their default port and endpoint.
+const ROLES: [(&str, &str, &str); 5] = [
+    ("node_exporter", "9300", "metrics"),
+    ("tserver", "9000", "prometheus-metrics"),
+    ("master", "7000", "prometheus-metrics"),
+    ("ysql", "13000", "prometheus-metrics"),
+    ("ycql", "12000", "prometheus-metrics"),
+];
+
+pub type ScrapeStatisticsMap = HashMap<String, ScrapeStatistics>;
+
 #[derive(Debug)]
 pub struct StoredNodeExporterValues {
     pub hostname_port: String,
@@ -53,6 +137,58 @@ pub struct CpuDetails {
     pub procs_blocked: f64,
     pub context_switches: f64,
     pub interrupts: f64,
+    pub node_boot_time: f64,
+}
+
+#[derive(Debug)]
+pub struct CpuCoreHost {
+    pub hostname_port: String,
+    pub timestamp: DateTime<Utc>,
+    pub cpucoredetail: Vec<CpuCoreDetail>,
+}
+
+#[derive(Debug)]
+pub struct CpuCoreDetail {
+    pub cpu_name: String,
+    pub cpu_idle: f64,
+    pub cpu_irq: f64,
+    pub cpu_softirq: f64,
+    pub cpu_system: f64,
+    pub cpu_user: f64,
+    pub cpu_iowait: f64,
+    pub cpu_nice: f64,
+    pub cpu_steal: f64,
+}
+
+#[derive(Debug)]
+pub struct MemoryDetails {
+    pub hostname_port: String,
+    pub timestamp: DateTime<Utc>,
+    pub mem_total: f64,
+    pub mem_free: f64,
+    pub buffers: f64,
+    pub cached: f64,
+    pub dirty: f64,
+    pub writeback: f64,
+    pub anon_pages: f64,
+    pub slab: f64,
+    pub swap_free: f64,
+    pub pgpgin: f64,
+    pub pgpgout: f64,
+    pub pswpin: f64,
+    pub pswpout: f64,
+    pub pgmajfault: f64,
+}
+
+#[derive(Debug)]
+pub struct PressureDetails {
+    pub hostname_port: String,
+    pub timestamp: DateTime<Utc>,
+    pub cpu_waiting: f64,
+    pub io_waiting: f64,
+    pub io_stalled: f64,
+    pub memory_waiting: f64,
+    pub memory_stalled: f64,
 }
 
 #[derive(Debug)]
@@ -60,6 +196,36 @@ pub struct DiskHost {
     pub hostname_port: String,
     pub timestamp: DateTime<Utc>,
     pub diskdetail: Vec<DiskDetail>,
+    pub node_boot_time: f64,
+}
+
+#[derive(Debug)]
+pub struct NetworkHost {
+    pub hostname_port: String,
+    pub timestamp: DateTime<Utc>,
+    pub networkdetail: Vec<NetworkDetail>,
+}
+
+#[derive(Debug)]
+pub struct TcpDetails {
+    pub hostname_port: String,
+    pub timestamp: DateTime<Utc>,
+    pub retrans_segs: f64,
+    pub in_segs: f64,
+    pub out_segs: f64,
+    pub listen_overflows: f64,
+    pub listen_drops: f64,
+    pub tcp_timeouts: f64,
+    pub tcp_inuse: f64,
+    pub tcp_tw: f64,
+    pub tcp_alloc: f64,
+}
+
+#[derive(Debug)]
+pub struct FilesystemHost {
+    pub hostname_port: String,
+    pub timestamp: DateTime<Utc>,
+    pub filesystemdetail: Vec<FilesystemDetail>,
 }
 
 #[derive(Debug)]
@@ -82,9 +248,177 @@ pub struct YugabyteIODetails {
     pub rocksdb_write_raw_block_micros_sum: f64,
     pub rocksdb_sst_read_micros_count: f64,
     pub rocksdb_sst_read_micros_sum: f64,
+    pub log_sync_latency_buckets: Vec<(f64, f64)>,
+    pub log_sync_latency_quantiles: Vec<(f64, f64)>,
+    pub log_append_latency_buckets: Vec<(f64, f64)>,
+    pub log_append_latency_quantiles: Vec<(f64, f64)>,
+    pub rocksdb_write_raw_block_micros_buckets: Vec<(f64, f64)>,
+    pub rocksdb_write_raw_block_micros_quantiles: Vec<(f64, f64)>,
+    pub rocksdb_sst_read_micros_buckets: Vec<(f64, f64)>,
+    pub rocksdb_sst_read_micros_quantiles: Vec<(f64, f64)>,
+    pub process_start_time: f64,
 }
 
 #[derive(Debug)]
+pub struct YugabyteRpcDetails {
+    pub hostname_port: String,
+    pub timestamp: DateTime<Utc>,
+    pub tserver_read_count: f64,
+    pub tserver_read_sum: f64,
+    pub tserver_write_count: f64,
+    pub tserver_write_sum: f64,
+    pub consensus_update_count: f64,
+    pub consensus_update_sum: f64,
+    pub rpc_incoming_queue_time_count: f64,
+    pub rpc_incoming_queue_time_sum: f64,
+    pub tserver_read_buckets: Vec<(f64, f64)>,
+    pub tserver_read_quantiles: Vec<(f64, f64)>,
+    pub tserver_write_buckets: Vec<(f64, f64)>,
+    pub tserver_write_quantiles: Vec<(f64, f64)>,
+    pub consensus_update_buckets: Vec<(f64, f64)>,
+    pub consensus_update_quantiles: Vec<(f64, f64)>,
+    pub rpc_incoming_queue_time_buckets: Vec<(f64, f64)>,
+    pub rpc_incoming_queue_time_quantiles: Vec<(f64, f64)>,
+    pub process_start_time: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct YBRpcPresentation {
+    pub timestamp: DateTime<Utc>,
+    pub tserver_read_count_diff: f64,
+    pub tserver_read_count_counter: f64,
+    pub tserver_read_sum_diff: f64,
+    pub tserver_read_sum_counter: f64,
+    pub tserver_write_count_diff: f64,
+    pub tserver_write_count_counter: f64,
+    pub tserver_write_sum_diff: f64,
+    pub tserver_write_sum_counter: f64,
+    pub consensus_update_count_diff: f64,
+    pub consensus_update_count_counter: f64,
+    pub consensus_update_sum_diff: f64,
+    pub consensus_update_sum_counter: f64,
+    pub rpc_incoming_queue_time_count_diff: f64,
+    pub rpc_incoming_queue_time_count_counter: f64,
+    pub rpc_incoming_queue_time_sum_diff: f64,
+    pub rpc_incoming_queue_time_sum_counter: f64,
+    #[serde(skip)]
+    pub tserver_read_buckets_counter: Vec<(f64, f64)>,
+    pub tserver_read_p50: f64,
+    pub tserver_read_p95: f64,
+    pub tserver_read_p99: f64,
+    #[serde(skip)]
+    pub tserver_write_buckets_counter: Vec<(f64, f64)>,
+    pub tserver_write_p50: f64,
+    pub tserver_write_p95: f64,
+    pub tserver_write_p99: f64,
+    #[serde(skip)]
+    pub consensus_update_buckets_counter: Vec<(f64, f64)>,
+    pub consensus_update_p50: f64,
+    pub consensus_update_p95: f64,
+    pub consensus_update_p99: f64,
+    #[serde(skip)]
+    pub rpc_incoming_queue_time_buckets_counter: Vec<(f64, f64)>,
+    pub rpc_incoming_queue_time_p50: f64,
+    pub rpc_incoming_queue_time_p95: f64,
+    pub rpc_incoming_queue_time_p99: f64,
+    pub process_start_time: f64,
+    pub reset: bool,
+}
+
+#[derive(Debug)]
+pub struct YugabyteYsqlDetails {
+    pub hostname_port: String,
+    pub timestamp: DateTime<Utc>,
+    pub select_count: f64,
+    pub select_sum: f64,
+    pub insert_count: f64,
+    pub insert_sum: f64,
+    pub update_count: f64,
+    pub update_sum: f64,
+    pub delete_count: f64,
+    pub delete_sum: f64,
+    pub transactions_count: f64,
+    pub transactions_sum: f64,
+    pub catalog_cache_misses_count: f64,
+    pub catalog_cache_misses_sum: f64,
+    pub process_start_time: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct YBYsqlPresentation {
+    pub timestamp: DateTime<Utc>,
+    pub select_count_diff: f64,
+    pub select_count_counter: f64,
+    pub select_sum_diff: f64,
+    pub select_sum_counter: f64,
+    pub insert_count_diff: f64,
+    pub insert_count_counter: f64,
+    pub insert_sum_diff: f64,
+    pub insert_sum_counter: f64,
+    pub update_count_diff: f64,
+    pub update_count_counter: f64,
+    pub update_sum_diff: f64,
+    pub update_sum_counter: f64,
+    pub delete_count_diff: f64,
+    pub delete_count_counter: f64,
+    pub delete_sum_diff: f64,
+    pub delete_sum_counter: f64,
+    pub transactions_count_diff: f64,
+    pub transactions_count_counter: f64,
+    pub transactions_sum_diff: f64,
+    pub transactions_sum_counter: f64,
+    pub catalog_cache_misses_count_diff: f64,
+    pub catalog_cache_misses_count_counter: f64,
+    pub catalog_cache_misses_sum_diff: f64,
+    pub catalog_cache_misses_sum_counter: f64,
+    pub process_start_time: f64,
+    pub reset: bool,
+}
+
+#[derive(Debug)]
+pub struct YugabyteYcqlDetails {
+    pub hostname_port: String,
+    pub timestamp: DateTime<Utc>,
+    pub select_count: f64,
+    pub select_sum: f64,
+    pub insert_count: f64,
+    pub insert_sum: f64,
+    pub execute_request_count: f64,
+    pub execute_request_sum: f64,
+    pub parse_request_count: f64,
+    pub parse_request_sum: f64,
+    pub cql_processors_alive: f64,
+    pub cql_processors_created: f64,
+    pub process_start_time: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct YBYcqlPresentation {
+    pub timestamp: DateTime<Utc>,
+    pub select_count_diff: f64,
+    pub select_count_counter: f64,
+    pub select_sum_diff: f64,
+    pub select_sum_counter: f64,
+    pub insert_count_diff: f64,
+    pub insert_count_counter: f64,
+    pub insert_sum_diff: f64,
+    pub insert_sum_counter: f64,
+    pub execute_request_count_diff: f64,
+    pub execute_request_count_counter: f64,
+    pub execute_request_sum_diff: f64,
+    pub execute_request_sum_counter: f64,
+    pub parse_request_count_diff: f64,
+    pub parse_request_count_counter: f64,
+    pub parse_request_sum_diff: f64,
+    pub parse_request_sum_counter: f64,
+    pub cql_processors_alive: f64,
+    pub cql_processors_created_diff: f64,
+    pub cql_processors_created_counter: f64,
+    pub process_start_time: f64,
+    pub reset: bool,
+}
+
+#[derive(Debug, Serialize)]
 pub struct YBIOPresentation {
     pub timestamp: DateTime<Utc>,
     pub glog_messages_info_diff: f64,
@@ -119,6 +453,28 @@ pub struct YBIOPresentation {
     pub rocksdb_sst_read_micros_count_counter: f64,
     pub rocksdb_sst_read_micros_sum_diff: f64,
     pub rocksdb_sst_read_micros_sum_counter: f64,
+    #[serde(skip)]
+    pub log_sync_latency_buckets_counter: Vec<(f64, f64)>,
+    pub log_sync_latency_p50: f64,
+    pub log_sync_latency_p95: f64,
+    pub log_sync_latency_p99: f64,
+    #[serde(skip)]
+    pub log_append_latency_buckets_counter: Vec<(f64, f64)>,
+    pub log_append_latency_p50: f64,
+    pub log_append_latency_p95: f64,
+    pub log_append_latency_p99: f64,
+    #[serde(skip)]
+    pub rocksdb_write_raw_block_micros_buckets_counter: Vec<(f64, f64)>,
+    pub rocksdb_write_raw_block_micros_p50: f64,
+    pub rocksdb_write_raw_block_micros_p95: f64,
+    pub rocksdb_write_raw_block_micros_p99: f64,
+    #[serde(skip)]
+    pub rocksdb_sst_read_micros_buckets_counter: Vec<(f64, f64)>,
+    pub rocksdb_sst_read_micros_p50: f64,
+    pub rocksdb_sst_read_micros_p95: f64,
+    pub rocksdb_sst_read_micros_p99: f64,
+    pub process_start_time: f64,
+    pub reset: bool,
 }
 
 #[derive(Debug)]
@@ -141,6 +497,31 @@ pub struct DiskDetail {
 }
 
 #[derive(Debug)]
+pub struct NetworkDetail {
+    pub interface_name: String,
+    pub receive_bytes: f64,
+    pub transmit_bytes: f64,
+    pub receive_packets: f64,
+    pub transmit_packets: f64,
+    pub receive_errs: f64,
+    pub transmit_errs: f64,
+    pub receive_drop: f64,
+    pub transmit_drop: f64,
+    pub speed_bytes: f64,
+}
+
+#[derive(Debug)]
+pub struct FilesystemDetail {
+    pub mountpoint: String,
+    pub device: String,
+    pub fstype: String,
+    pub size_bytes: f64,
+    pub avail_bytes: f64,
+    pub files: f64,
+    pub files_free: f64,
+}
+
+#[derive(Debug, Serialize)]
 pub struct CpuPresentation {
     pub timestamp: DateTime<Utc>,
     pub idle_diff: f64,
@@ -176,9 +557,74 @@ pub struct CpuPresentation {
     pub context_switches_counter: f64,
     pub interrupts_diff: f64,
     pub interrupts_counter: f64,
+    pub node_boot_time: f64,
+    pub reset: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+pub struct CpuCorePresentation {
+    pub timestamp: DateTime<Utc>,
+    pub idle_diff: f64,
+    pub idle_counter: f64,
+    pub irq_diff: f64,
+    pub irq_counter: f64,
+    pub softirq_diff: f64,
+    pub softirq_counter: f64,
+    pub system_diff: f64,
+    pub system_counter: f64,
+    pub user_diff: f64,
+    pub user_counter: f64,
+    pub iowait_diff: f64,
+    pub iowait_counter: f64,
+    pub nice_diff: f64,
+    pub nice_counter: f64,
+    pub steal_diff: f64,
+    pub steal_counter: f64,
+    pub reset: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemoryPresentation {
+    pub timestamp: DateTime<Utc>,
+    pub mem_total: f64,
+    pub mem_free: f64,
+    pub buffers: f64,
+    pub cached: f64,
+    pub dirty: f64,
+    pub writeback: f64,
+    pub anon_pages: f64,
+    pub slab: f64,
+    pub swap_free: f64,
+    pub pgpgin_diff: f64,
+    pub pgpgin_counter: f64,
+    pub pgpgout_diff: f64,
+    pub pgpgout_counter: f64,
+    pub pswpin_diff: f64,
+    pub pswpin_counter: f64,
+    pub pswpout_diff: f64,
+    pub pswpout_counter: f64,
+    pub pgmajfault_diff: f64,
+    pub pgmajfault_counter: f64,
+    pub reset: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PressurePresentation {
+    pub timestamp: DateTime<Utc>,
+    pub cpu_waiting_diff: f64,
+    pub cpu_waiting_counter: f64,
+    pub io_waiting_diff: f64,
+    pub io_waiting_counter: f64,
+    pub io_stalled_diff: f64,
+    pub io_stalled_counter: f64,
+    pub memory_waiting_diff: f64,
+    pub memory_waiting_counter: f64,
+    pub memory_stalled_diff: f64,
+    pub memory_stalled_counter: f64,
+    pub reset: bool,
+}
+
+#[derive(Debug, Serialize)]
 pub struct DiskPresentation {
     pub timestamp: DateTime<Utc>,
     pub reads_completed_diff: f64,
@@ -209,70 +655,898 @@ pub struct DiskPresentation {
     pub disk_total_time_counter: f64,
     pub queue_diff: f64,
     pub queue_counter: f64,
+    pub node_boot_time: f64,
+    pub reset: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NetworkPresentation {
+    pub timestamp: DateTime<Utc>,
+    pub receive_bytes_diff: f64,
+    pub receive_bytes_counter: f64,
+    pub transmit_bytes_diff: f64,
+    pub transmit_bytes_counter: f64,
+    pub receive_packets_diff: f64,
+    pub receive_packets_counter: f64,
+    pub transmit_packets_diff: f64,
+    pub transmit_packets_counter: f64,
+    pub receive_errs_diff: f64,
+    pub receive_errs_counter: f64,
+    pub transmit_errs_diff: f64,
+    pub transmit_errs_counter: f64,
+    pub receive_drop_diff: f64,
+    pub receive_drop_counter: f64,
+    pub transmit_drop_diff: f64,
+    pub transmit_drop_counter: f64,
+    pub speed_bytes: f64,
+    pub reset: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TcpPresentation {
+    pub timestamp: DateTime<Utc>,
+    pub retrans_segs_diff: f64,
+    pub retrans_segs_counter: f64,
+    pub in_segs_diff: f64,
+    pub in_segs_counter: f64,
+    pub out_segs_diff: f64,
+    pub out_segs_counter: f64,
+    pub listen_overflows_diff: f64,
+    pub listen_overflows_counter: f64,
+    pub listen_drops_diff: f64,
+    pub listen_drops_counter: f64,
+    pub tcp_timeouts_diff: f64,
+    pub tcp_timeouts_counter: f64,
+    pub tcp_inuse: f64,
+    pub tcp_tw: f64,
+    pub tcp_alloc: f64,
+    pub reset: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FilesystemPresentation {
+    pub timestamp: DateTime<Utc>,
+    pub device: String,
+    pub fstype: String,
+    pub size_bytes: f64,
+    pub avail_bytes_diff: f64,
+    pub avail_bytes_counter: f64,
+    pub files: f64,
+    pub files_free: f64,
+    pub reset: bool,
+}
+
+pub fn create_http_client(
+    connection: &Connection,
+) -> Result<reqwest::blocking::Client, String> {
+    let mut client_builder = reqwest::blocking::Client::builder()
+        .connect_timeout(Duration::from_secs(connection.connect_timeout))
+        .timeout(Duration::from_secs(connection.read_timeout))
+        .danger_accept_invalid_certs(connection.insecure);
+    if let Some(ca_cert) = &connection.ca_cert {
+        let ca_bundle = fs::read(ca_cert).map_err(|e| format!("error reading CA bundle {}: {}", ca_cert, e))?;
+        for certificate in reqwest::Certificate::from_pem_bundle(&ca_bundle).map_err(|e| format!("error parsing CA bundle {}: {}", ca_cert, e))? {
+            client_builder = client_builder.add_root_certificate(certificate);
+        }
+    }
+    match (&connection.client_cert, &connection.client_key) {
+        (Some(client_cert), Some(client_key)) => {
+            let certificate = fs::read(client_cert).map_err(|e| format!("error reading client certificate {}: {}", client_cert, e))?;
+            let key = fs::read(client_key).map_err(|e| format!("error reading client key {}: {}", client_key, e))?;
+            let identity = reqwest::Identity::from_pkcs8_pem(&certificate, &key).map_err(|e| format!("error parsing client certificate {} and key {}: {}", client_cert, client_key, e))?;
+            client_builder = client_builder.identity(identity);
+        },
+        (None, None) => {},
+        _ => return Err("a client certificate needs both client_cert and client_key".to_string()),
+    }
+    client_builder.build().map_err(|e| format!("error creating http client: {}", e))
+}
+
+fn connection_with_settings(
+    connection: &Connection,
+    settings: &[&str],
+) -> Result<Connection, String> {
+    // the settings of a target in a targets file override the settings given on the command line.
+    let mut connection = connection.clone();
+    for setting in settings {
+        match setting.split_once('=').unwrap_or((setting, "")) {
+            ("ca_cert", value) => connection.ca_cert = Some(value.to_string()),
+            ("client_cert", value) => connection.client_cert = Some(value.to_string()),
+            ("client_key", value) => connection.client_key = Some(value.to_string()),
+            ("insecure", value) => connection.insecure = value.is_empty() || value == "true",
+            ("user", value) => connection.user = Some(value.to_string()),
+            ("password", value) => connection.password = Some(value.to_string()),
+            ("password_file", value) => connection.password = Some(read_secret_file(value)?),
+            ("bearer_token", value) => connection.bearer_token = Some(value.to_string()),
+            ("bearer_token_file", value) => connection.bearer_token = Some(read_secret_file(value)?),
+            (name, _) => return Err(format!("unknown setting {}, settings are: ca_cert, client_cert, client_key, insecure, user, password, password_file, bearer_token, bearer_token_file", name)),
+        }
+    }
+    Ok(connection)
+}
+
+pub fn read_secret_file(
+    file: &str,
+) -> Result<String, String> {
+    // a password or token file usually ends with a newline, which is not part of the secret.
+    fs::read_to_string(file)
+        .map(|secret| secret.trim_end_matches(['\r', '\n']).to_string())
+        .map_err(|e| format!("error reading secret file {}: {}", file, e))
+}
+
+fn http_client_for(
+    connection: &Connection,
+    clients: &mut Vec<(Connection, reqwest::blocking::Client)>,
+) -> Result<reqwest::blocking::Client, String> {
+    // targets with the same settings share a client, so that they share its connections.
+    if let Some((_, client)) = clients.iter().find(|(client_connection, _)| client_connection == connection) {
+        return Ok(client.clone());
+    }
+    let client = create_http_client(connection)?;
+    clients.push((connection.clone(), client.clone()));
+    Ok(client)
+}
+
+pub fn parse_targets(
+    hosts: &[&str],
+    ports: &[&str],
+    connection: &Connection,
+) -> Result<Vec<Target>, String> {
+    let mut clients = Vec::new();
+    let mut targets = Vec::new();
+    for host in hosts.iter().filter(|x| !x.is_empty()) {
+        let client = http_client_for(connection, &mut clients)?;
+        targets.extend(parse_target(host, ports, connection, &client)?);
+    }
+    Ok(targets)
+}
+
+pub fn read_targets_file(
+    file: &str,
+    ports: &[&str],
+    connection: &Connection,
+) -> Result<Vec<Target>, String> {
+    // every line is a target, optionally followed by settings for the target, such as:
+    // https://tserver@10.0.0.1 ca_cert=/opt/yugabyte/certs/ca.crt user=yugabyte password_file=/etc/nodetop/password
+    let targets_file = fs::read_to_string(file).map_err(|e| format!("error reading targets file {}: {}", file, e))?;
+    let mut clients = Vec::new();
+    let mut targets = Vec::new();
+    for line in targets_file.lines().map(|line| line.trim()).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let target_connection = connection_with_settings(connection, &fields[1..])?;
+        let client = http_client_for(&target_connection, &mut clients)?;
+        targets.extend(parse_target(fields[0], ports, &target_connection, &client)?);
+    }
+    Ok(targets)
+}
+
+fn parse_target(
+    host: &str,
+    ports: &[&str],
+    connection: &Connection,
+    client: &reqwest::blocking::Client,
+) -> Result<Vec<Target>, String> {
+    // a host can be given as [scheme://][role@]host[:port][/endpoint].
+    // a host without role and port is combined with the ports, a role without port uses the default port of the role.
+    // if the endpoint is given or follows from the role, it is used as is, otherwise it is found by probing.
+    let (scheme, role_host_port_endpoint) = match host.split_once("://") {
+        Some((scheme @ ("http" | "https"), role_host_port_endpoint)) => (scheme, role_host_port_endpoint),
+        Some((scheme, _)) => return Err(format!("unknown scheme {} in {}, schemes are: http, https", scheme, host)),
+        None => ("http", host),
+    };
+    let (role, host_port_endpoint) = match role_host_port_endpoint.split_once('@') {
+        Some((role, host_port_endpoint)) => {
+            match ROLES.iter().find(|(name, _, _)| *name == role) {
+                Some(role) => (Some(role), host_port_endpoint),
+                None => return Err(format!("unknown role {} in {}, roles are: {}", role, host, ROLES.iter().map(|(name, _, _)| *name).collect::<Vec<_>>().join(", "))),
+            }
+        },
+        None => (None, role_host_port_endpoint),
+    };
+    let (host_port, endpoint) = match host_port_endpoint.split_once('/') {
+        Some((host_port, endpoint)) => (host_port, Some(endpoint.to_string())),
+        None => (host_port_endpoint, role.map(|(_, _, endpoint)| endpoint.to_string())),
+    };
+    let target = |hostname: &str, port: &str| Target {
+        role: role.map(|(name, _, _)| name.to_string()),
+        scheme: scheme.to_string(),
+        host: hostname.to_string(),
+        port: port.to_string(),
+        endpoint: endpoint.clone(),
+        no_endpoint: false,
+        connection: connection.clone(),
+        client: client.clone(),
+    };
+    match (host_port.split_once(':'), role) {
+        (Some((hostname, port)), _) => Ok(vec![target(hostname, port)]),
+        (None, Some((_, port, _))) => Ok(vec![target(host_port, port)]),
+        (None, None) => Ok(ports.iter().filter(|x| !x.is_empty()).map(|port| target(host_port, port)).collect()),
+    }
+}
+
+pub fn parse_master(
+    master: &str,
+    connection: &Connection,
+) -> Result<Target, String> {
+    // the master is given as [scheme://]host[:port], and is a target with the master role.
+    let (scheme, host_port) = match master.split_once("://") {
+        Some((scheme, host_port)) => (format!("{}://", scheme), host_port),
+        None => (String::new(), master),
+    };
+    let client = create_http_client(connection)?;
+    let mut targets = parse_target(&format!("{}master@{}", scheme, host_port), &[], connection, &client)?;
+    Ok(targets.remove(0))
+}
+
+pub fn parse_node_exporter_template(
+    scheme: &str,
+    settings: &str,
+    connection: &Connection,
+) -> Result<Target, String> {
+    // the node_exporters discovered from a master have a scheme and settings of their own, and only take the timeouts
+    // from the command line, so that the certificates and credentials of the master are not sent to them.
+    let node_exporter_connection = Connection {
+        connect_timeout: connection.connect_timeout,
+        read_timeout: connection.read_timeout,
+        ..Default::default()
+    };
+    let node_exporter_connection = connection_with_settings(&node_exporter_connection, &settings.split_whitespace().collect::<Vec<_>>())?;
+    let client = create_http_client(&node_exporter_connection)?;
+    Ok(Target {
+        role: Some("node_exporter".to_string()),
+        scheme: scheme.to_string(),
+        host: String::new(),
+        port: String::new(),
+        endpoint: None,
+        no_endpoint: false,
+        connection: node_exporter_connection,
+        client,
+    })
+}
+
+pub fn target_hostname_port(
+    target: &Target,
+) -> String {
+    let role = target.role.as_ref().map(|role| format!("{}@", role)).unwrap_or_default();
+    format!("{}{}:{}", role, target.host, target.port)
+}
+
+fn http_get(
+    target: &Target,
+    path: &str,
+) -> reqwest::blocking::RequestBuilder {
+    let request = target.client.get(format!("{}://{}:{}/{}", target.scheme, target.host, target.port, path));
+    match (&target.connection.bearer_token, &target.connection.user) {
+        (Some(bearer_token), _) => request.bearer_auth(bearer_token),
+        (None, Some(user)) => request.basic_auth(user, target.connection.password.as_ref()),
+        (None, None) => request,
+    }
+}
+
+fn role_target(
+    template: &Target,
+    role: &str,
+    host: &str,
+    port: &str,
+) -> Target {
+    // a discovered master or tablet server uses the scheme and settings of the master it is discovered from,
+    // a discovered node_exporter those of the node_exporter template.
+    Target {
+        role: Some(role.to_string()),
+        host: host.to_string(),
+        port: port.to_string(),
+        endpoint: ROLES.iter().find(|(name, _, _)| *name == role).map(|(_, _, endpoint)| endpoint.to_string()),
+        no_endpoint: false,
+        ..template.clone()
+    }
+}
+
+pub fn discover_cluster(
+    masters: &[Target],
+    node_exporter: &Target,
+) -> Result<Vec<Target>, String> {
+    // the masters are tried in order, so that the cluster can still be read if a master is down.
+    let mut error = String::from("no master to discover the cluster from");
+    for master in masters {
+        match discover_cluster_from_master(master, node_exporter) {
+            Ok(targets) => return Ok(targets),
+            Err(master_error) => error = master_error,
+        }
+    }
+    Err(error)
+}
+
+fn discover_cluster_from_master(
+    master: &Target,
+    node_exporter: &Target,
+) -> Result<Vec<Target>, String> {
+    let masters_json = read_json(master, "api/v1/masters")?;
+    let tablet_servers_json = read_json(master, "api/v1/tablet-servers")?;
+    let mut targets = Vec::new();
+    // the masters are a list, with the http addresses in the registration.
+    for master_json in masters_json["masters"].as_array().into_iter().flatten() {
+        for http_address in master_json["registration"]["http_addresses"].as_array().into_iter().flatten() {
+            if let (Some(host), Some(port)) = (http_address["host"].as_str(), http_address["port"].as_u64()) {
+                targets.push(role_target(master, "master", host, &port.to_string()));
+            }
+        }
+    }
+    // the tablet servers are grouped by placement uuid, with their http address as key.
+    for placement in tablet_servers_json.as_object().into_iter().flat_map(|placements| placements.values()) {
+        for http_address in placement.as_object().into_iter().flat_map(|tablet_servers| tablet_servers.keys()) {
+            if let Some((host, port)) = http_address.rsplit_once(':') {
+                targets.push(role_target(master, "tserver", host, port));
+            }
+        }
+    }
+    if targets.is_empty() {
+        return Err(format!("no masters or tablet servers found at master {}:{}", master.host, master.port));
+    }
+    // node_exporter is expected on its default port on every host of the cluster.
+    let hosts: BTreeSet<String> = targets.iter().map(|target| target.host.clone()).collect();
+    for host in hosts {
+        targets.push(role_target(node_exporter, "node_exporter", &host, "9300"));
+    }
+    Ok(targets)
+}
+
+fn read_json(
+    target: &Target,
+    path: &str,
+) -> Result<serde_json::Value, String> {
+    let data_from_http = http_get(target, path)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .map_err(|e| format!("error reading from URL: {}", e))?;
+    serde_json::from_str(&data_from_http).map_err(|e| format!("error parsing json from {}:{}/{}: {}", target.host, target.port, path, e))
+}
+
+pub fn discover_endpoints(
+    targets: &mut [Target],
+    pool: &rayon::ThreadPool,
+) {
+    // only the targets for which the endpoint is not known yet are probed, so an endpoint that could not be reached
+    // at startup is probed again the next time. a host that was reached, but serves no prometheus endpoint, is not.
+    if targets.iter().all(|target| target.endpoint.is_some() || target.no_endpoint) {
+        return;
+    }
+    let probe_targets: &[Target] = targets;
+    let (tx, rx) = channel();
+    pool.scope(move |s| {
+        for (index, target) in probe_targets.iter().enumerate().filter(|(_, target)| target.endpoint.is_none() && !target.no_endpoint) {
+            let tx = tx.clone();
+            s.spawn(move |_| {
+                let endpoint = probe_endpoint(target);
+                tx.send((index, endpoint)).expect("error sending data via tx (probe)");
+            });
+        }
+    });
+    for (index, endpoint) in rx {
+        match endpoint {
+            Ok(Some(endpoint)) => targets[index].endpoint = Some(endpoint),
+            Ok(None) => targets[index].no_endpoint = true,
+            Err(_) => {},
+        }
+    }
+}
+
+fn probe_endpoint(
+    target: &Target,
+) -> Result<Option<String>, String> {
+    // yugabyte serves prometheus format on prometheus-metrics and json on metrics, node_exporter only serves metrics.
+    // prometheus-metrics is tried first, so the (large) yugabyte json is not read.
+    // node_exporter answers any other path with its html landing page, so an endpoint is only taken if it is prometheus
+    // text: either by its content type, or because samples can be parsed from it.
+    // an error means the host could not be reached, Ok(None) that it was reached but serves no prometheus endpoint.
+    for endpoint in ["prometheus-metrics", "metrics"] {
+        let response = http_get(target, endpoint).send().map_err(|e| format!("error reading from URL: {}", e))?;
+        if !response.status().is_success() {
+            continue;
+        }
+        let prometheus_content_type = response.headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .map(|content_type| content_type.starts_with("text/plain") && content_type.contains("version=0.0.4"))
+            .unwrap_or(false);
+        if prometheus_content_type {
+            return Ok(Some(endpoint.to_string()));
+        }
+        let data_from_http = response.text().map_err(|e| format!("error reading from URL: {}", e))?;
+        if !parse_node_exporter(data_from_http, Utc::now()).is_empty() {
+            return Ok(Some(endpoint.to_string()));
+        }
+    }
+    Ok(None)
 }
 
 pub fn read_node_exporter_into_map(
-    hosts: &Vec<&str>,
-    ports: &Vec<&str>,
-    parallel: usize,
-) -> HashMap<String, Vec<NodeExporterValues>> {
-    let pool = rayon::ThreadPoolBuilder::new().num_threads(parallel).build().unwrap();
+    targets: &[Target],
+    pool: &rayon::ThreadPool,
+    metric_filter: Option<&str>,
+) -> (HashMap<String, Vec<NodeExporterValues>>, HashMap<String, String>, ScrapeStatisticsMap) {
     let (tx, rx) = channel();
     pool.scope(move |s| {
-        for host in hosts {
-            for port in ports {
-                let endpoints = vec!["metrics", "prometheus-metrics"];
-                for endpoint in endpoints {
-                    let tx = tx.clone();
-                    s.spawn(move |_| {
-                        let detail_snapshot_time = Local::now();
-                        let node_exporter_values = read_node_exporter(&host, &port, &endpoint);
-                        tx.send((format!("{}:{}:{}", host, port, endpoint), detail_snapshot_time, node_exporter_values)).expect("error sending data via tx (node_exporter)");
-                    });
-                }
+        for target in targets {
+            // a target for which no endpoint is found is not read.
+            if let Some(endpoint) = &target.endpoint {
+                let tx = tx.clone();
+                s.spawn(move |_| {
+                    let scrape_start = Instant::now();
+                    let node_exporter_values = read_node_exporter(target, endpoint, metric_filter);
+                    let scrape_duration = scrape_start.elapsed();
+                    tx.send((format!("{}:{}", target_hostname_port(target), endpoint), scrape_duration, node_exporter_values)).expect("error sending data via tx (node_exporter)");
+                });
             }
         }
     });
+    // an endpoint that could not be read is not added to the values, but to the unreachable endpoints with the error.
     let mut map_exporter_values: HashMap<String, Vec<NodeExporterValues>> = HashMap::new();
-    for (hostname_port, _detail_snapshot_time, node_exporter_values) in rx {
-        map_exporter_values.insert( hostname_port, node_exporter_values);
+    let mut map_unreachable: HashMap<String, String> = HashMap::new();
+    let mut map_scrape_statistics: ScrapeStatisticsMap = HashMap::new();
+    for (hostname_port, duration, node_exporter_values) in rx {
+        match node_exporter_values {
+            Ok((node_exporter_values, size)) => {
+                map_scrape_statistics.insert( hostname_port.clone(), ScrapeStatistics { duration, size });
+                map_exporter_values.insert( hostname_port, node_exporter_values);
+            },
+            Err(error) => {
+                map_scrape_statistics.insert( hostname_port.clone(), ScrapeStatistics { duration, size: 0 });
+                map_unreachable.insert( hostname_port, error);
+            },
+        }
+    }
+    (map_exporter_values, map_unreachable, map_scrape_statistics)
+}
+
+// the metric names that the yugabyte modes use, to be sent as the metrics parameter to the yugabyte prometheus-metrics endpoint,
+// which then only returns these metrics. histograms and summaries are given by their name without _count or _sum.
+pub const YUGABYTE_IO_METRICS: [&str; 14] = [
+    "glog_info_messages",
+    "glog_warning_messages",
+    "glog_error_messages",
+    "log_bytes_logged",
+    "log_reader_bytes_read",
+    "log_cache_disk_reads",
+    "log_sync_latency",
+    "log_append_latency",
+    "rocksdb_flush_write_bytes",
+    "rocksdb_compact_read_bytes",
+    "rocksdb_compact_write_bytes",
+    "rocksdb_write_raw_block_micros",
+    "rocksdb_sst_read_micros",
+    "process_start_time_seconds",
+];
+pub const YUGABYTE_RPC_METRICS: [&str; 5] = [
+    "handler_latency_yb_tserver_TabletServerService_Read",
+    "handler_latency_yb_tserver_TabletServerService_Write",
+    "handler_latency_yb_consensus_ConsensusService_UpdateConsensus",
+    "rpc_incoming_queue_time",
+    "process_start_time_seconds",
+];
+pub const YUGABYTE_YSQL_METRICS: [&str; 7] = [
+    "handler_latency_yb_ysqlserver_SQLProcessor_SelectStmt",
+    "handler_latency_yb_ysqlserver_SQLProcessor_InsertStmt",
+    "handler_latency_yb_ysqlserver_SQLProcessor_UpdateStmt",
+    "handler_latency_yb_ysqlserver_SQLProcessor_DeleteStmt",
+    "handler_latency_yb_ysqlserver_SQLProcessor_Transactions",
+    "handler_latency_yb_ysqlserver_SQLProcessor_CatalogCacheMisses",
+    "process_start_time_seconds",
+];
+pub const YUGABYTE_YCQL_METRICS: [&str; 7] = [
+    "handler_latency_yb_cqlserver_SQLProcessor_SelectStmt",
+    "handler_latency_yb_cqlserver_SQLProcessor_InsertStmt",
+    "handler_latency_yb_cqlserver_SQLProcessor_ExecuteRequest",
+    "handler_latency_yb_cqlserver_SQLProcessor_ParseRequest",
+    "cql_processors_alive",
+    "cql_processors_created",
+    "process_start_time_seconds",
+];
+
+// the node_exporter metric names that the modes use, to select only these series when reading from prometheus.
+pub const NODE_EXPORTER_CPU_METRICS: [&str; 12] = [
+    "node_cpu_seconds_total",
+    "node_cpu_guest_seconds_total",
+    "node_schedstat_running_seconds_total",
+    "node_schedstat_waiting_seconds_total",
+    "node_procs_running",
+    "node_procs_blocked",
+    "node_intr_total",
+    "node_context_switches_total",
+    "node_load1",
+    "node_load5",
+    "node_load15",
+    "node_boot_time_seconds",
+];
+pub const NODE_EXPORTER_MEMORY_METRICS: [&str; 14] = [
+    "node_memory_MemTotal_bytes",
+    "node_memory_MemFree_bytes",
+    "node_memory_Buffers_bytes",
+    "node_memory_Cached_bytes",
+    "node_memory_Dirty_bytes",
+    "node_memory_Writeback_bytes",
+    "node_memory_AnonPages_bytes",
+    "node_memory_Slab_bytes",
+    "node_memory_SwapFree_bytes",
+    "node_vmstat_pgpgin",
+    "node_vmstat_pgpgout",
+    "node_vmstat_pswpin",
+    "node_vmstat_pswpout",
+    "node_vmstat_pgmajfault",
+];
+pub const NODE_EXPORTER_PRESSURE_METRICS: [&str; 5] = [
+    "node_pressure_cpu_waiting_seconds_total",
+    "node_pressure_io_waiting_seconds_total",
+    "node_pressure_io_stalled_seconds_total",
+    "node_pressure_memory_waiting_seconds_total",
+    "node_pressure_memory_stalled_seconds_total",
+];
+pub const NODE_EXPORTER_DISK_METRICS: [&str; 15] = [
+    "node_disk_reads_completed_total",
+    "node_disk_writes_completed_total",
+    "node_disk_reads_merged_total",
+    "node_disk_writes_merged_total",
+    "node_disk_read_bytes_total",
+    "node_disk_written_bytes_total",
+    "node_disk_read_time_seconds_total",
+    "node_disk_write_time_seconds_total",
+    "node_disk_io_time_seconds_total",
+    "node_disk_io_time_weighted_seconds_total",
+    "node_disk_discards_completed_total",
+    "node_disk_discards_merged_total",
+    "node_disk_discarded_sectors_total",
+    "node_disk_discard_time_seconds_total",
+    "node_boot_time_seconds",
+];
+pub const NODE_EXPORTER_FILESYSTEM_METRICS: [&str; 4] = [
+    "node_filesystem_size_bytes",
+    "node_filesystem_avail_bytes",
+    "node_filesystem_files",
+    "node_filesystem_files_free",
+];
+pub const NODE_EXPORTER_NETWORK_METRICS: [&str; 9] = [
+    "node_network_receive_bytes_total",
+    "node_network_transmit_bytes_total",
+    "node_network_receive_packets_total",
+    "node_network_transmit_packets_total",
+    "node_network_receive_errs_total",
+    "node_network_transmit_errs_total",
+    "node_network_receive_drop_total",
+    "node_network_transmit_drop_total",
+    "node_network_speed_bytes",
+];
+pub const NODE_EXPORTER_TCP_METRICS: [&str; 9] = [
+    "node_netstat_Tcp_RetransSegs",
+    "node_netstat_Tcp_InSegs",
+    "node_netstat_Tcp_OutSegs",
+    "node_netstat_TcpExt_ListenOverflows",
+    "node_netstat_TcpExt_ListenDrops",
+    "node_netstat_TcpExt_TCPTimeouts",
+    "node_sockstat_TCP_inuse",
+    "node_sockstat_TCP_tw",
+    "node_sockstat_TCP_alloc",
+];
+
+// a series read from prometheus: its labels, and the timestamp and value of a sample.
+type PrometheusSeries<'a> = (&'a serde_json::Value, DateTime<Utc>, &'a str);
+
+pub fn parse_prometheus(
+    prometheus: &str,
+    connection: &Connection,
+) -> Result<Target, String> {
+    // the prometheus server is given as [scheme://]host[:port][/path], the path is the prefix of the api, if any.
+    let client = create_http_client(connection)?;
+    let mut targets = parse_target(prometheus, &["9090"], connection, &client)?;
+    if targets.is_empty() {
+        return Err(format!("invalid prometheus server {}", prometheus));
+    }
+    Ok(targets.remove(0))
+}
+
+fn prometheus_api(
+    prometheus: &Target,
+    api: &str,
+) -> String {
+    match prometheus.endpoint.as_deref().map(|path| path.trim_matches('/')) {
+        Some(path) if !path.is_empty() => format!("{}/api/v1/{}", path, api),
+        _ => format!("api/v1/{}", api),
+    }
+}
+
+fn read_prometheus_api(
+    prometheus: &Target,
+    api: &str,
+    parameters: &[(&str, String)],
+) -> Result<Vec<serde_json::Value>, String> {
+    let data_from_http = http_get(prometheus, &prometheus_api(prometheus, api))
+        .query(parameters)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .map_err(|e| format!("error reading from prometheus: {}", e))?;
+    let json: serde_json::Value = serde_json::from_str(&data_from_http).map_err(|e| format!("error parsing json from prometheus: {}", e))?;
+    if json["status"] != "success" {
+        return Err(format!("error from prometheus: {}", json["error"]));
+    }
+    Ok(json["data"]["result"].as_array().cloned().unwrap_or_default())
+}
+
+pub fn read_prometheus_into_map(
+    prometheus: &Target,
+    selector: &str,
+    lookback: u64,
+) -> Result<HashMap<String, Vec<NodeExporterValues>>, String> {
+    // a range selector returns the samples with the time they were scraped, instead of the time of the query.
+    // the last sample of every series is used, so the rates are calculated over the time between the scrapes.
+    let result = read_prometheus_api(prometheus, "query", &[("query", format!("{}[{}s]", selector, lookback))])?;
+    let mut series = Vec::new();
+    for result_series in &result {
+        if let Some(sample) = result_series["values"].as_array().and_then(|values| values.last()) {
+            if let Some((timestamp, value)) = prometheus_sample(sample) {
+                series.push((&result_series["metric"], timestamp, value));
+            }
+        }
+    }
+    Ok(prometheus_series_into_map(&series))
+}
+
+pub fn read_prometheus_range(
+    prometheus: &Target,
+    selector: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    step: u64,
+) -> Result<Vec<HashMap<String, Vec<NodeExporterValues>>>, String> {
+    // every step of the range is turned into the values of an interval, in the order of time.
+    let result = read_prometheus_api(prometheus, "query_range", &[
+        ("query", selector.to_string()),
+        ("start", start.timestamp().to_string()),
+        ("end", end.timestamp().to_string()),
+        ("step", format!("{}s", step)),
+    ])?;
+    let mut steps: BTreeMap<i64, Vec<PrometheusSeries>> = BTreeMap::new();
+    for result_series in &result {
+        for sample in result_series["values"].as_array().into_iter().flatten() {
+            if let Some((timestamp, value)) = prometheus_sample(sample) {
+                steps.entry(timestamp.timestamp_millis()).or_default().push((&result_series["metric"], timestamp, value));
+            }
+        }
+    }
+    Ok(steps.values().map(|series| prometheus_series_into_map(series)).collect())
+}
+
+fn prometheus_sample(
+    sample: &serde_json::Value,
+) -> Option<(DateTime<Utc>, &str)> {
+    // a sample is a list of the timestamp in seconds and the value as string.
+    let timestamp = sample[0].as_f64()?;
+    let value = sample[1].as_str()?;
+    Some((DateTime::<Utc>::from(UNIX_EPOCH + Duration::from_secs_f64(timestamp)), value))
+}
+
+fn prometheus_series_into_map(
+    series: &[PrometheusSeries],
+) -> HashMap<String, Vec<NodeExporterValues>> {
+    // the series are grouped by the instance and job labels that prometheus added when scraping, which take the place of
+    // hostname:port:endpoint. the other labels are turned back into prometheus format, so that they are parsed the
+    // same as when read from the endpoint.
+    let mut endpoints: BTreeMap<String, (DateTime<Utc>, Vec<String>)> = BTreeMap::new();
+    for (metric, timestamp, value) in series {
+        let labels = match metric.as_object() {
+            Some(labels) => labels,
+            None => continue,
+        };
+        let name = labels.get("__name__").and_then(|x| x.as_str()).unwrap_or_default();
+        let instance = labels.get("instance").and_then(|x| x.as_str()).unwrap_or_default();
+        let job = labels.get("job").and_then(|x| x.as_str()).unwrap_or_default();
+        let sample_labels = labels.iter()
+            .filter(|(label, _)| !["__name__", "instance", "job"].contains(&label.as_str()))
+            .map(|(label, label_value)| format!("{}=\"{}\"", label, label_value.as_str().unwrap_or_default().replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")))
+            .collect::<Vec<String>>()
+            .join(",");
+        let endpoint = endpoints.entry(format!("{}:{}", instance, job)).or_insert((*timestamp, Vec::new()));
+        endpoint.0 = endpoint.0.max(*timestamp);
+        if sample_labels.is_empty() {
+            endpoint.1.push(format!("{} {}", name, value));
+        } else {
+            endpoint.1.push(format!("{}{{{}}} {}", name, sample_labels, value));
+        }
+    }
+    endpoints.into_iter()
+        .map(|(hostname_port, (fetch_time, lines))| (hostname_port, parse_node_exporter(lines.join("\n"), fetch_time)))
+        .collect()
+}
+
+// the values and unreachable endpoints of an interval, as read from a recording.
+pub type RecordedInterval = (HashMap<String, Vec<NodeExporterValues>>, HashMap<String, String>);
+
+pub fn record_scrapes(
+    record_file: &mut impl Write,
+    node_values: &HashMap<String, Vec<NodeExporterValues>>,
+    unreachable: &HashMap<String, String>,
+) -> Result<(), String> {
+    // a recording is gzip compressed tab separated text, with a line for every interval (I), for every endpoint that is read
+    // in the interval with the time it was read (S), for every value of the endpoint (V) and for every unreachable endpoint (U).
+    let mut lines = format!("I\t{}\n", Utc::now().timestamp_nanos());
+    for (hostname_port, values) in node_values.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+        let fetch_time = values.first().map(|value| value.node_exporter_timestamp).unwrap_or_else(Utc::now);
+        lines.push_str(&format!("S\t{}\t{}\n", hostname_port, fetch_time.timestamp_nanos()));
+        for value in values {
+            lines.push_str(&format!("V\t{}\t{}\t{}\t{}\t{}\n", value.node_exporter_name, value.node_exporter_type, escape_recorded_labels(&value.node_exporter_labels), value.node_exporter_category, value.node_exporter_value));
+        }
+    }
+    for (hostname_port, error) in unreachable.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+        lines.push_str(&format!("U\t{}\t{}\n", hostname_port, error.replace(['\t', '\n'], " ")));
+    }
+    // every interval is written as a gzip member of its own, so a recording that is appended to, or that is stopped
+    // at any moment, can still be read in full.
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(lines.as_bytes())
+        .and_then(|_| encoder.finish())
+        .and_then(|member| record_file.write_all(&member))
+        .and_then(|_| record_file.flush())
+        .map_err(|e| format!("error writing recording: {}", e))
+}
+
+// the labels are label values, such as mountpoints and table names, which can contain a tab or a newline.
+// these are escaped like label values in prometheus format, so every value stays on a line of its own.
+fn escape_recorded_labels(
+    labels: &str,
+) -> String {
+    labels.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape_recorded_labels(
+    labels: &str,
+) -> String {
+    let mut unescaped = String::with_capacity(labels.len());
+    let mut characters = labels.chars();
+    while let Some(character) = characters.next() {
+        match character {
+            '\\' => match characters.next() {
+                Some('t') => unescaped.push('\t'),
+                Some('n') => unescaped.push('\n'),
+                Some(escaped) => unescaped.push(escaped),
+                None => unescaped.push('\\'),
+            },
+            character => unescaped.push(character),
+        }
+    }
+    unescaped
+}
+
+// a recording is read an interval at a time, so a long recording does not have to fit in memory.
+pub struct Recording {
+    file: String,
+    reader: BufReader<MultiGzDecoder<fs::File>>,
+    line_number: usize,
+}
+
+impl Iterator for Recording {
+    type Item = Result<RecordedInterval, String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        read_recorded_interval(self).transpose()
+    }
+}
+
+pub fn read_recording(
+    file: &str,
+) -> Result<Recording, String> {
+    let recording = fs::File::open(file).map_err(|e| format!("error reading recording {}: {}", file, e))?;
+    Ok(Recording {
+        file: file.to_string(),
+        reader: BufReader::new(MultiGzDecoder::new(recording)),
+        line_number: 0,
+    })
+}
+
+fn read_recorded_interval(
+    recording: &mut Recording,
+) -> Result<Option<RecordedInterval>, String> {
+    // an interval starts with its I line, and ends where the next interval starts or where the recording ends.
+    let mut interval: Option<RecordedInterval> = None;
+    let mut endpoint: Option<(String, DateTime<Utc>)> = None;
+    loop {
+        let read_error = |e: std::io::Error| format!("error reading recording {}: {}", recording.file, e);
+        match recording.reader.fill_buf().map_err(read_error)?.first() {
+            None => return Ok(interval),
+            Some(b'I') if interval.is_some() => return Ok(interval),
+            Some(_) => {},
+        }
+        let mut line = String::new();
+        recording.reader.read_line(&mut line).map_err(read_error)?;
+        recording.line_number += 1;
+        let line = line.trim_end_matches('\n');
+        let fields: Vec<&str> = line.split('\t').collect();
+        let invalid = || format!("invalid line {} in recording {}: {}", recording.line_number, recording.file, line);
+        let timestamp = |nanos: &str| nanos.parse::<u64>().map(|nanos| DateTime::<Utc>::from(UNIX_EPOCH + Duration::from_nanos(nanos))).map_err(|_| invalid());
+        match (fields.as_slice(), interval.as_mut(), &endpoint) {
+            (["I", _], None, _) => {
+                interval = Some((HashMap::new(), HashMap::new()));
+            },
+            (["S", hostname_port, fetch_time], Some(_), _) => {
+                endpoint = Some((hostname_port.to_string(), timestamp(fetch_time)?));
+            },
+            (["V", name, value_type, labels, category, value], Some((node_values, _)), Some((hostname_port, fetch_time))) => {
+                node_values.entry(hostname_port.clone()).or_default().push(
+                    NodeExporterValues {
+                        node_exporter_name: name.to_string(),
+                        node_exporter_type: value_type.to_string(),
+                        node_exporter_labels: unescape_recorded_labels(labels),
+                        node_exporter_category: category.to_string(),
+                        node_exporter_value: value.parse::<f64>().map_err(|_| invalid())?,
+                        node_exporter_timestamp: *fetch_time,
+                    }
+                );
+            },
+            (["U", hostname_port, error], Some((_, unreachable)), _) => {
+                unreachable.insert(hostname_port.to_string(), error.to_string());
+            },
+            _ => return Err(invalid()),
+        }
     }
-    map_exporter_values
 }
 
 pub fn read_node_exporter(
-    host: &str,
-    port: &str,
+    target: &Target,
     endpoint: &str,
-) -> Vec<NodeExporterValues> {
-    if !scan_port_addr(format!("{}:{}", host, port)) {
-        println!("Warning! hostname:port {}:{} cannot be reached, skipping (node_exporter)", host, port);
-        return parse_node_exporter(String::from(""));
+    metric_filter: Option<&str>,
+) -> Result<(Vec<NodeExporterValues>, usize), String> {
+    // the metric filter is only understood by yugabyte, which serves prometheus-metrics.
+    let request = match metric_filter {
+        Some(metric_filter) if endpoint == "prometheus-metrics" => http_get(target, endpoint).query(&[("metrics", metric_filter)]),
+        _ => http_get(target, endpoint),
     };
-    let data_from_http = reqwest::blocking::get(format!("http://{}:{}/{}", host, port, endpoint))
-        .unwrap_or_else(|e| {
-            eprintln!("Fatal: error reading from URL: {}", e);
-            process::exit(1);
-        })
-        .text().unwrap();
-    parse_node_exporter(data_from_http)
+    let data_from_http = request
+        .send()
+        .and_then(|response| response.error_for_status())
+        .and_then(|response| response.text())
+        .map_err(|e| {
+            if e.is_connect() {
+                format!("hostname:port {}:{} cannot be reached", target.host, target.port)
+            } else if e.is_timeout() {
+                format!("timeout reading from URL: {}", e)
+            } else {
+                format!("error reading from URL: {}", e)
+            }
+        })?;
+    // the samples get the time the endpoint was read, so that the rates are calculated over the time between the reads
+    // of this endpoint, regardless of how long the other endpoints took to read.
+    let fetch_time = Utc::now();
+    let size = data_from_http.len();
+    Ok((parse_node_exporter(data_from_http, fetch_time), size))
 }
 
-fn parse_node_exporter(node_exporter_data: String) -> Vec<NodeExporterValues> {
-    let lines: Vec<_> = node_exporter_data.lines().map(|s| Ok(s.to_owned())).collect();
+fn parse_node_exporter(node_exporter_data: String, fetch_time: DateTime<Utc>) -> Vec<NodeExporterValues> {
+    // prometheus_parse only recognizes a histogram bucket or summary quantile if le or quantile is the first label,
+    // and then throws away all the other labels.
+    // therefore the type lines of histograms and summaries are removed, which makes their samples untyped with all labels,
+    // and these are recognized by their le or quantile label.
+    let lines: Vec<_> = node_exporter_data
+        .lines()
+        .filter(|s| !matches!(s.split_whitespace().collect::<Vec<_>>()[..], ["#", "TYPE", _, "histogram" | "summary", ..]))
+        .map(|s| Ok(s.to_owned()))
+        .collect();
     let node_exporter_rows = prometheus_parse::Scrape::parse(lines.into_iter()).unwrap();
     let mut nodeexportervalues = Vec::new();
 
-    if node_exporter_rows.samples.len() > 0 {
+    if !node_exporter_rows.samples.is_empty() {
         for sample in node_exporter_rows.samples {
             let mut label_temp = sample.labels.values().cloned().collect::<Vec<String>>();
             label_temp.sort();
             let mut label = label_temp.join("_");
-            label = if label.len() > 0 {
+            label = if !label.is_empty() {
                 format!("_{}", label)
             } else {
                 label
             };
+            // the filesystem labels (device, fstype and mountpoint) cannot be told apart after sorting,
+            // so for these the label is set to _fstype device mountpoint.
+            // the fstype and device contain no spaces, the mountpoint can, which is why it is last.
+            if sample.metric.starts_with("node_filesystem_") {
+                label = format!("_{} {} {}", sample.labels.get("fstype").unwrap_or_default(), sample.labels.get("device").unwrap_or_default(), sample.labels.get("mountpoint").unwrap_or_default());
+            }
 
             match sample.value {
                 Value::Counter(val) => {
@@ -282,7 +1556,7 @@ fn parse_node_exporter(node_exporter_data: String) -> Vec<NodeExporterValues> {
                             node_exporter_type: "counter".to_string(),
                             node_exporter_labels: label,
                             node_exporter_category: "all".to_string(),
-                            node_exporter_timestamp: sample.timestamp,
+                            node_exporter_timestamp: fetch_time,
                             node_exporter_value: val,
                         }
                     )
@@ -294,12 +1568,40 @@ fn parse_node_exporter(node_exporter_data: String) -> Vec<NodeExporterValues> {
                             node_exporter_type: "gauge".to_string(),
                             node_exporter_labels: label,
                             node_exporter_category: "all".to_string(),
-                            node_exporter_timestamp: sample.timestamp,
+                            node_exporter_timestamp: fetch_time,
                             node_exporter_value: val,
                         }
                     )
                 }
                 Value::Untyped(val) => {
+                    // histogram bucket: stored as a record per bucket, with the upper bound of the bucket as label.
+                    if let (true, Some(less_than)) = (sample.metric.ends_with("_bucket"), sample.labels.get("le").and_then(|x| x.parse::<f64>().ok())) {
+                        nodeexportervalues.push(
+                            NodeExporterValues {
+                                node_exporter_name: sample.metric.trim_end_matches("_bucket").to_string(),
+                                node_exporter_type: "histogram".to_string(),
+                                node_exporter_labels: format!("_{}", less_than),
+                                node_exporter_category: "all".to_string(),
+                                node_exporter_timestamp: fetch_time,
+                                node_exporter_value: val,
+                            }
+                        );
+                        continue;
+                    };
+                    // summary quantile: stored as a record per quantile, with the quantile as label.
+                    if let Some(quantile) = sample.labels.get("quantile").and_then(|x| x.parse::<f64>().ok()) {
+                        nodeexportervalues.push(
+                            NodeExporterValues {
+                                node_exporter_name: sample.metric.to_string(),
+                                node_exporter_type: "summary".to_string(),
+                                node_exporter_labels: format!("_{}", quantile),
+                                node_exporter_category: "all".to_string(),
+                                node_exporter_timestamp: fetch_time,
+                                node_exporter_value: val,
+                            }
+                        );
+                        continue;
+                    };
                     // it turns out summary type _sum and _count values are untyped values.
                     // so I remove them here.
                     //if sample.metric.ends_with("_sum") || sample.metric.ends_with("_count") { continue; };
@@ -311,14 +1613,15 @@ fn parse_node_exporter(node_exporter_data: String) -> Vec<NodeExporterValues> {
                             node_exporter_type: "counter".to_string(),
                             node_exporter_labels: label,
                             node_exporter_category: "all".to_string(),
-                            node_exporter_timestamp: sample.timestamp,
+                            node_exporter_timestamp: fetch_time,
                             node_exporter_value: val,
 
                         }
                     )
                 }
-                Value::Summary(_val) => {}
-                Value::Histogram(_val) => {}
+                // histograms and summaries are not produced, because their type lines are removed before parsing.
+                // the match has to be exhaustive, so they are matched here, but there is nothing to store for them.
+                Value::Histogram(_) | Value::Summary(_) => {}
             }
         }
         // glog_(info|warning|error)_messages => glog_messages_total
@@ -735,60 +2038,185 @@ fn parse_node_exporter(node_exporter_data: String) -> Vec<NodeExporterValues> {
                 node_exporter_timestamp: nodeexportervalues.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total").filter(|r| r.node_exporter_labels.contains("steal")).map(|x| x.node_exporter_timestamp).min().unwrap(),
                 node_exporter_value: nodeexportervalues.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total").filter(|r| r.node_exporter_labels.contains("steal")).map(|x| x.node_exporter_value).sum(),
             });
-        }
+        }
+    }
+    nodeexportervalues
+}
+
+pub fn add_to_node_exporter_vectors(
+    node_exporter_values: Vec<NodeExporterValues>,
+    hostname: &str,
+    stored_node_exporter_values: &mut Vec<StoredNodeExporterValues>,
+) {
+    for row in node_exporter_values {
+        stored_node_exporter_values.push(
+            StoredNodeExporterValues {
+                hostname_port: hostname.to_string(),
+                timestamp: DateTime::from(row.node_exporter_timestamp),
+                node_exporter_name: row.node_exporter_name.to_string(),
+                node_exporter_type: row.node_exporter_type.to_string(),
+                node_exporter_labels: row.node_exporter_labels.to_string(),
+                node_exporter_category: row.node_exporter_category.to_string(),
+                node_exporter_value: row.node_exporter_value,
+            }
+        );
+    }
+}
+
+pub fn cpu_details(
+    values: &HashMap<String, Vec<NodeExporterValues>>
+) -> Vec<CpuDetails>
+{
+    let mut details: Vec<CpuDetails> = Vec::new();
+    for (hostname_port, node_exporter_vector) in values {
+        if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_load1").count() > 0 {
+            details.push(CpuDetails {
+                hostname_port: hostname_port.to_string(),
+                timestamp: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_load1").map(|x| x.node_exporter_timestamp).next().unwrap(),
+                node_boot_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_boot_time_seconds").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                load_1: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_load1").map(|x| x.node_exporter_value).next().unwrap(),
+                load_5: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_load5").map(|x| x.node_exporter_value).next().unwrap(),
+                load_15: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_load15").map(|x| x.node_exporter_value).next().unwrap(),
+                cpu_idle: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_idle" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                cpu_irq: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_irq" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                cpu_softirq: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_softirq" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                cpu_system: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_system" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                cpu_user: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_user" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                cpu_iowait: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_iowait" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                cpu_nice: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_nice" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                cpu_steal: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_steal" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                cpu_guest_user: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_guest_seconds_total" && r.node_exporter_labels == "_user" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                cpu_guest_nice: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_guest_seconds_total" && r.node_exporter_labels == "_nice" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                schedstat_running: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_schedstat_running_seconds_total" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                schedstat_waiting: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_schedstat_waiting_seconds_total" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                procs_running: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_procs_running").map(|x| x.node_exporter_value).next().unwrap(),
+                procs_blocked: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_procs_blocked").map(|x| x.node_exporter_value).next().unwrap(),
+                context_switches: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_context_switches_total").map(|x| x.node_exporter_value).next().unwrap(),
+                interrupts: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_intr_total").map(|x| x.node_exporter_value).next().unwrap(),
+            });
+        };
+    }
+    details
+}
+
+pub fn cpu_core_details(
+    values: &HashMap<String, Vec<NodeExporterValues>>
+) -> Vec<CpuCoreHost>
+{
+    let mut details: Vec<CpuCoreHost> = Vec::new();
+    for (hostname_port, node_exporter_vector) in values {
+        if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_category == "detail").count() > 0 {
+            // the detail records have the label _cpu_mode, these are grouped by cpu once, with the value of every mode.
+            let mut cpus: BTreeMap<&str, HashMap<&str, f64>> = BTreeMap::new();
+            for record in node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_category == "detail") {
+                if let Some((cpu, mode)) = record.node_exporter_labels.strip_prefix('_').and_then(|labels| labels.split_once('_')) {
+                    cpus.entry(cpu).or_default().insert(mode, record.node_exporter_value);
+                }
+            }
+            // every cpu has an idle record.
+            let cpucorestats: Vec<CpuCoreDetail> = cpus
+                .into_iter()
+                .filter(|(_, modes)| modes.contains_key("idle"))
+                .map(|(cpu, modes)| {
+                    let mode = |name: &str| modes.get(name).copied().unwrap_or_default();
+                    CpuCoreDetail {
+                        cpu_idle: mode("idle"),
+                        cpu_irq: mode("irq"),
+                        cpu_softirq: mode("softirq"),
+                        cpu_system: mode("system"),
+                        cpu_user: mode("user"),
+                        cpu_iowait: mode("iowait"),
+                        cpu_nice: mode("nice"),
+                        cpu_steal: mode("steal"),
+                        cpu_name: format!("cpu{}", cpu),
+                    }
+                })
+                .collect();
+            details.push(
+                CpuCoreHost {
+                    hostname_port: hostname_port.to_string(),
+                    timestamp: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total").map(|x| x.node_exporter_timestamp).next().unwrap(),
+                    cpucoredetail: cpucorestats,
+                }
+            );
+        }
+    }
+    details
+}
+
+pub fn memory_details(
+    values: &HashMap<String, Vec<NodeExporterValues>>
+) -> Vec<MemoryDetails>
+{
+    let mut details: Vec<MemoryDetails> = Vec::new();
+    for (hostname_port, node_exporter_vector) in values {
+        if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_memory_MemTotal_bytes").count() > 0 {
+            details.push(MemoryDetails {
+                hostname_port: hostname_port.to_string(),
+                timestamp: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_memory_MemTotal_bytes").map(|x| x.node_exporter_timestamp).next().unwrap(),
+                mem_total: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_memory_MemTotal_bytes").map(|x| x.node_exporter_value).next().unwrap(),
+                mem_free: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_memory_MemFree_bytes").map(|x| x.node_exporter_value).next().unwrap(),
+                buffers: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_memory_Buffers_bytes").map(|x| x.node_exporter_value).next().unwrap(),
+                cached: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_memory_Cached_bytes").map(|x| x.node_exporter_value).next().unwrap(),
+                dirty: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_memory_Dirty_bytes").map(|x| x.node_exporter_value).next().unwrap(),
+                writeback: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_memory_Writeback_bytes").map(|x| x.node_exporter_value).next().unwrap(),
+                anon_pages: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_memory_AnonPages_bytes").map(|x| x.node_exporter_value).next().unwrap(),
+                slab: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_memory_Slab_bytes").map(|x| x.node_exporter_value).next().unwrap(),
+                swap_free: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_memory_SwapFree_bytes").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                // the vmstat collector can be disabled, in which case these counters are absent.
+                pgpgin: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_vmstat_pgpgin").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                pgpgout: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_vmstat_pgpgout").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                pswpin: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_vmstat_pswpin").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                pswpout: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_vmstat_pswpout").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                pgmajfault: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_vmstat_pgmajfault").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+            });
+        };
     }
-    nodeexportervalues
+    details
 }
 
-pub fn add_to_node_exporter_vectors(
-    node_exporter_values: Vec<NodeExporterValues>,
-    hostname: &str,
-    stored_node_exporter_values: &mut Vec<StoredNodeExporterValues>,
-) {
-    for row in node_exporter_values {
-        stored_node_exporter_values.push(
-            StoredNodeExporterValues {
-                hostname_port: hostname.to_string(),
-                timestamp: DateTime::from(row.node_exporter_timestamp),
-                node_exporter_name: row.node_exporter_name.to_string(),
-                node_exporter_type: row.node_exporter_type.to_string(),
-                node_exporter_labels: row.node_exporter_labels.to_string(),
-                node_exporter_category: row.node_exporter_category.to_string(),
-                node_exporter_value: row.node_exporter_value,
-            }
-        );
+pub fn pressure_details(
+    values: &HashMap<String, Vec<NodeExporterValues>>
+) -> Vec<PressureDetails>
+{
+    let mut details: Vec<PressureDetails> = Vec::new();
+    for (hostname_port, node_exporter_vector) in values {
+        // pressure stall information is only available with linux 4.20 and newer, and node_exporter 1.0 and newer.
+        if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_pressure_cpu_waiting_seconds_total").count() > 0 {
+            details.push(PressureDetails {
+                hostname_port: hostname_port.to_string(),
+                timestamp: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_pressure_cpu_waiting_seconds_total").map(|x| x.node_exporter_timestamp).next().unwrap(),
+                cpu_waiting: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_pressure_cpu_waiting_seconds_total").map(|x| x.node_exporter_value).next().unwrap(),
+                io_waiting: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_pressure_io_waiting_seconds_total").map(|x| x.node_exporter_value).next().unwrap(),
+                io_stalled: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_pressure_io_stalled_seconds_total").map(|x| x.node_exporter_value).next().unwrap(),
+                memory_waiting: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_pressure_memory_waiting_seconds_total").map(|x| x.node_exporter_value).next().unwrap(),
+                memory_stalled: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_pressure_memory_stalled_seconds_total").map(|x| x.node_exporter_value).next().unwrap(),
+            });
+        };
     }
+    details
 }
 
-pub fn cpu_details(
+pub fn tcp_details(
     values: &HashMap<String, Vec<NodeExporterValues>>
-) -> Vec<CpuDetails>
+) -> Vec<TcpDetails>
 {
-    let mut details: Vec<CpuDetails> = Vec::new();
+    let mut details: Vec<TcpDetails> = Vec::new();
     for (hostname_port, node_exporter_vector) in values {
-        if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_load1").count() > 0 {
-            details.push(CpuDetails {
+        if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_netstat_Tcp_InSegs").count() > 0 {
+            details.push(TcpDetails {
                 hostname_port: hostname_port.to_string(),
-                timestamp: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_load1").map(|x| x.node_exporter_timestamp).nth(0).unwrap(),
-                load_1: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_load1").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                load_5: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_load5").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                load_15: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_load15").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                cpu_idle: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_idle" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                cpu_irq: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_irq" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                cpu_softirq: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_softirq" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                cpu_system: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_system" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                cpu_user: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_user" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                cpu_iowait: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_iowait" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                cpu_nice: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_nice" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                cpu_steal: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_seconds_total" && r.node_exporter_labels == "_steal" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                cpu_guest_user: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_guest_seconds_total" && r.node_exporter_labels == "_user" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                cpu_guest_nice: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_cpu_guest_seconds_total" && r.node_exporter_labels == "_nice" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                schedstat_running: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_schedstat_running_seconds_total" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                schedstat_waiting: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_schedstat_waiting_seconds_total" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                procs_running: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_procs_running").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                procs_blocked: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_procs_blocked").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                context_switches: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_context_switches_total").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                interrupts: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_intr_total").map(|x| x.node_exporter_value).nth(0).unwrap(),
+                timestamp: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_netstat_Tcp_InSegs").map(|x| x.node_exporter_timestamp).next().unwrap(),
+                retrans_segs: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_netstat_Tcp_RetransSegs").map(|x| x.node_exporter_value).next().unwrap(),
+                in_segs: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_netstat_Tcp_InSegs").map(|x| x.node_exporter_value).next().unwrap(),
+                out_segs: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_netstat_Tcp_OutSegs").map(|x| x.node_exporter_value).next().unwrap(),
+                // the TcpExt statistics depend on the netstat fields node_exporter is configured to expose.
+                listen_overflows: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_netstat_TcpExt_ListenOverflows").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                listen_drops: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_netstat_TcpExt_ListenDrops").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                tcp_timeouts: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_netstat_TcpExt_TCPTimeouts").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                tcp_inuse: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_sockstat_TCP_inuse").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                tcp_tw: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_sockstat_TCP_tw").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                tcp_alloc: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_sockstat_TCP_alloc").map(|x| x.node_exporter_value).next().unwrap_or_default(),
             });
         };
     }
@@ -804,23 +2232,32 @@ pub fn yugabyte_details(
         if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_bytes_logged").count() > 0 {
             details.push(YugabyteIODetails {
                 hostname_port: hostname_port.to_string(),
-                timestamp: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_bytes_logged").map(|x| x.node_exporter_timestamp).nth(0).unwrap(),
-                glog_messages_info: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "glog_messages_info" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                glog_messages_prio: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "glog_messages_prio" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                log_bytes_logged: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_bytes_logged" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                log_reader_bytes_read: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_reader_bytes_read" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                log_sync_latency_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_sync_latency_count" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                log_sync_latency_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_sync_latency_sum" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                log_append_latency_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_append_latency_count" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                log_append_latency_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_append_latency_sum" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                log_cache_disk_reads: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_cache_disk_reads" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                rocksdb_flush_write_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_flush_write_bytes" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                rocksdb_compact_read_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_compact_read_bytes" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                rocksdb_compact_write_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_compact_write_bytes" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                rocksdb_write_raw_block_micros_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_write_raw_block_micros_count" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                rocksdb_write_raw_block_micros_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_write_raw_block_micros_sum" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                rocksdb_sst_read_micros_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_sst_read_micros_count" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
-                rocksdb_sst_read_micros_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_sst_read_micros_sum" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap(),
+                timestamp: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_bytes_logged").map(|x| x.node_exporter_timestamp).next().unwrap(),
+                process_start_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "process_start_time_seconds").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                glog_messages_info: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "glog_messages_info" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                glog_messages_prio: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "glog_messages_prio" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                log_bytes_logged: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_bytes_logged" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                log_reader_bytes_read: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_reader_bytes_read" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                log_sync_latency_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_sync_latency_count" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                log_sync_latency_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_sync_latency_sum" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                log_append_latency_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_append_latency_count" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                log_append_latency_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_append_latency_sum" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                log_cache_disk_reads: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "log_cache_disk_reads" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                rocksdb_flush_write_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_flush_write_bytes" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                rocksdb_compact_read_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_compact_read_bytes" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                rocksdb_compact_write_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_compact_write_bytes" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                rocksdb_write_raw_block_micros_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_write_raw_block_micros_count" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                rocksdb_write_raw_block_micros_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_write_raw_block_micros_sum" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                rocksdb_sst_read_micros_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_sst_read_micros_count" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                rocksdb_sst_read_micros_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_sst_read_micros_sum" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).next().unwrap(),
+                log_sync_latency_buckets: histogram_buckets(node_exporter_vector, "log_sync_latency"),
+                log_sync_latency_quantiles: summary_quantiles(node_exporter_vector, "log_sync_latency"),
+                log_append_latency_buckets: histogram_buckets(node_exporter_vector, "log_append_latency"),
+                log_append_latency_quantiles: summary_quantiles(node_exporter_vector, "log_append_latency"),
+                rocksdb_write_raw_block_micros_buckets: histogram_buckets(node_exporter_vector, "rocksdb_write_raw_block_micros"),
+                rocksdb_write_raw_block_micros_quantiles: summary_quantiles(node_exporter_vector, "rocksdb_write_raw_block_micros"),
+                rocksdb_sst_read_micros_buckets: histogram_buckets(node_exporter_vector, "rocksdb_sst_read_micros"),
+                rocksdb_sst_read_micros_quantiles: summary_quantiles(node_exporter_vector, "rocksdb_sst_read_micros"),
             });
             //println!("{}", node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rocksdb_flush_write_bytes" && r.node_exporter_category == "summary").map(|x| x.node_exporter_value).nth(0).unwrap());
         };
@@ -828,6 +2265,169 @@ pub fn yugabyte_details(
     details
 }
 
+pub fn yugabyte_rpc_details(
+    values: &HashMap<String, Vec<NodeExporterValues>>
+) -> Vec<YugabyteRpcDetails>
+{
+    // the handler latency and rpc queue time metrics are server level metrics,
+    // these are summed in case a server exposes multiple of them.
+    let mut details: Vec<YugabyteRpcDetails> = Vec::new();
+    for (hostname_port, node_exporter_vector) in values {
+        if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rpc_incoming_queue_time_count").count() > 0 {
+            details.push(YugabyteRpcDetails {
+                hostname_port: hostname_port.to_string(),
+                timestamp: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rpc_incoming_queue_time_count").map(|x| x.node_exporter_timestamp).next().unwrap(),
+                process_start_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "process_start_time_seconds").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                tserver_read_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_tserver_TabletServerService_Read_count").map(|x| x.node_exporter_value).sum(),
+                tserver_read_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_tserver_TabletServerService_Read_sum").map(|x| x.node_exporter_value).sum(),
+                tserver_write_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_tserver_TabletServerService_Write_count").map(|x| x.node_exporter_value).sum(),
+                tserver_write_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_tserver_TabletServerService_Write_sum").map(|x| x.node_exporter_value).sum(),
+                consensus_update_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_consensus_ConsensusService_UpdateConsensus_count").map(|x| x.node_exporter_value).sum(),
+                consensus_update_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_consensus_ConsensusService_UpdateConsensus_sum").map(|x| x.node_exporter_value).sum(),
+                rpc_incoming_queue_time_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rpc_incoming_queue_time_count").map(|x| x.node_exporter_value).sum(),
+                rpc_incoming_queue_time_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "rpc_incoming_queue_time_sum").map(|x| x.node_exporter_value).sum(),
+                tserver_read_buckets: histogram_buckets(node_exporter_vector, "handler_latency_yb_tserver_TabletServerService_Read"),
+                tserver_read_quantiles: summary_quantiles(node_exporter_vector, "handler_latency_yb_tserver_TabletServerService_Read"),
+                tserver_write_buckets: histogram_buckets(node_exporter_vector, "handler_latency_yb_tserver_TabletServerService_Write"),
+                tserver_write_quantiles: summary_quantiles(node_exporter_vector, "handler_latency_yb_tserver_TabletServerService_Write"),
+                consensus_update_buckets: histogram_buckets(node_exporter_vector, "handler_latency_yb_consensus_ConsensusService_UpdateConsensus"),
+                consensus_update_quantiles: summary_quantiles(node_exporter_vector, "handler_latency_yb_consensus_ConsensusService_UpdateConsensus"),
+                rpc_incoming_queue_time_buckets: histogram_buckets(node_exporter_vector, "rpc_incoming_queue_time"),
+                rpc_incoming_queue_time_quantiles: summary_quantiles(node_exporter_vector, "rpc_incoming_queue_time"),
+            });
+        };
+    }
+    details
+}
+
+pub fn yugabyte_ysql_details(
+    values: &HashMap<String, Vec<NodeExporterValues>>
+) -> Vec<YugabyteYsqlDetails>
+{
+    // the ysql statement metrics are provided by the postgres metrics endpoint (port 13000, prometheus-metrics).
+    let mut details: Vec<YugabyteYsqlDetails> = Vec::new();
+    for (hostname_port, node_exporter_vector) in values {
+        if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_SelectStmt_count").count() > 0 {
+            details.push(YugabyteYsqlDetails {
+                hostname_port: hostname_port.to_string(),
+                timestamp: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_SelectStmt_count").map(|x| x.node_exporter_timestamp).next().unwrap(),
+                process_start_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "process_start_time_seconds").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                select_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_SelectStmt_count").map(|x| x.node_exporter_value).sum(),
+                select_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_SelectStmt_sum").map(|x| x.node_exporter_value).sum(),
+                insert_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_InsertStmt_count").map(|x| x.node_exporter_value).sum(),
+                insert_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_InsertStmt_sum").map(|x| x.node_exporter_value).sum(),
+                update_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_UpdateStmt_count").map(|x| x.node_exporter_value).sum(),
+                update_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_UpdateStmt_sum").map(|x| x.node_exporter_value).sum(),
+                delete_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_DeleteStmt_count").map(|x| x.node_exporter_value).sum(),
+                delete_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_DeleteStmt_sum").map(|x| x.node_exporter_value).sum(),
+                transactions_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_Transactions_count").map(|x| x.node_exporter_value).sum(),
+                transactions_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_Transactions_sum").map(|x| x.node_exporter_value).sum(),
+                catalog_cache_misses_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_CatalogCacheMisses_count").map(|x| x.node_exporter_value).sum(),
+                catalog_cache_misses_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_ysqlserver_SQLProcessor_CatalogCacheMisses_sum").map(|x| x.node_exporter_value).sum(),
+            });
+        };
+    }
+    details
+}
+
+pub fn yugabyte_ycql_details(
+    values: &HashMap<String, Vec<NodeExporterValues>>
+) -> Vec<YugabyteYcqlDetails>
+{
+    // the ycql statement metrics are provided by the cql server metrics endpoint (port 12000, prometheus-metrics).
+    let mut details: Vec<YugabyteYcqlDetails> = Vec::new();
+    for (hostname_port, node_exporter_vector) in values {
+        if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_cqlserver_SQLProcessor_SelectStmt_count").count() > 0 {
+            details.push(YugabyteYcqlDetails {
+                hostname_port: hostname_port.to_string(),
+                timestamp: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_cqlserver_SQLProcessor_SelectStmt_count").map(|x| x.node_exporter_timestamp).next().unwrap(),
+                process_start_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "process_start_time_seconds").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                select_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_cqlserver_SQLProcessor_SelectStmt_count").map(|x| x.node_exporter_value).sum(),
+                select_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_cqlserver_SQLProcessor_SelectStmt_sum").map(|x| x.node_exporter_value).sum(),
+                insert_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_cqlserver_SQLProcessor_InsertStmt_count").map(|x| x.node_exporter_value).sum(),
+                insert_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_cqlserver_SQLProcessor_InsertStmt_sum").map(|x| x.node_exporter_value).sum(),
+                execute_request_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_cqlserver_SQLProcessor_ExecuteRequest_count").map(|x| x.node_exporter_value).sum(),
+                execute_request_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_cqlserver_SQLProcessor_ExecuteRequest_sum").map(|x| x.node_exporter_value).sum(),
+                parse_request_count: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_cqlserver_SQLProcessor_ParseRequest_count").map(|x| x.node_exporter_value).sum(),
+                parse_request_sum: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "handler_latency_yb_cqlserver_SQLProcessor_ParseRequest_sum").map(|x| x.node_exporter_value).sum(),
+                cql_processors_alive: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "cql_processors_alive").map(|x| x.node_exporter_value).sum(),
+                cql_processors_created: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "cql_processors_created").map(|x| x.node_exporter_value).sum(),
+            });
+        };
+    }
+    details
+}
+
+pub fn histogram_buckets(
+    node_exporter_vector: &[NodeExporterValues],
+    node_exporter_name: &str,
+) -> Vec<(f64, f64)> {
+    // a histogram can have multiple label sets, such as per tablet,
+    // the buckets with the same upper bound are summed, which makes it the histogram for the server.
+    let mut buckets: Vec<(f64, f64)> = Vec::new();
+    for record in node_exporter_vector.iter().filter(|r| r.node_exporter_name == node_exporter_name && r.node_exporter_type == "histogram") {
+        let less_than = record.node_exporter_labels[1..].parse::<f64>().unwrap_or(f64::INFINITY);
+        match buckets.iter_mut().find(|(bucket_less_than, _)| *bucket_less_than == less_than) {
+            Some(bucket) => bucket.1 += record.node_exporter_value,
+            None => buckets.push((less_than, record.node_exporter_value)),
+        }
+    }
+    buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+    buckets
+}
+
+pub fn summary_quantiles(
+    node_exporter_vector: &[NodeExporterValues],
+    node_exporter_name: &str,
+) -> Vec<(f64, f64)> {
+    // quantiles cannot be added up, so if there are multiple, the highest value is taken.
+    let mut quantiles: Vec<(f64, f64)> = Vec::new();
+    for record in node_exporter_vector.iter().filter(|r| r.node_exporter_name == node_exporter_name && r.node_exporter_type == "summary") {
+        let quantile = record.node_exporter_labels[1..].parse::<f64>().unwrap_or_default();
+        match quantiles.iter_mut().find(|(quantile_found, _)| *quantile_found == quantile) {
+            Some(found) => found.1 = found.1.max(record.node_exporter_value),
+            None => quantiles.push((quantile, record.node_exporter_value)),
+        }
+    }
+    quantiles
+}
+
+pub fn latency_percentile(
+    buckets: &[(f64, f64)],
+    previous_buckets: &[(f64, f64)],
+    quantiles: &[(f64, f64)],
+    percentile: f64,
+) -> f64 {
+    // cumulative buckets: the percentile is calculated from the bucket deltas between the two scrapes,
+    // with linear interpolation inside the bucket, like prometheus histogram_quantile() does.
+    if !buckets.is_empty() && !previous_buckets.is_empty() {
+        // the buckets are summed over the tablets of a server, so a bucket goes down when a tablet moves to another server.
+        // a negative delta is taken as no change, and if the deltas are not cumulative anymore, there is no percentile.
+        let deltas: Vec<(f64, f64)> = buckets
+            .iter()
+            .map(|(less_than, count)| (*less_than, (count - previous_buckets.iter().filter(|(previous_less_than, _)| previous_less_than == less_than).map(|(_, previous_count)| previous_count).next().unwrap_or(&0.)).max(0.)))
+            .collect();
+        if deltas.windows(2).any(|pair| pair[1].1 < pair[0].1) { return 0. };
+        let total = deltas.last().map(|(_, count)| *count).unwrap_or_default();
+        if total <= 0. { return 0. };
+        let rank = percentile * total;
+        let mut lower_bound = 0.;
+        let mut lower_count = 0.;
+        for (less_than, count) in deltas {
+            if count >= rank {
+                if less_than.is_infinite() { return lower_bound };
+                if count == lower_count { return less_than };
+                return lower_bound + (less_than - lower_bound) * (rank - lower_count) / (count - lower_count);
+            }
+            lower_bound = less_than;
+            lower_count = count;
+        }
+        return lower_bound;
+    }
+    // summary: the quantiles are calculated by the exporter.
+    quantiles.iter().filter(|(quantile, _)| (quantile - percentile).abs() < 0.0001).map(|(_, value)| *value).next().unwrap_or_default()
+}
+
 pub fn disk_details(
     values: &HashMap<String, Vec<NodeExporterValues>>
 ) -> Vec<DiskHost>
@@ -839,27 +2439,99 @@ pub fn disk_details(
             for row in node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_reads_completed_total").filter(|r| !r.node_exporter_labels.contains("dm-")).map(|x| x.node_exporter_labels.clone()) {
                 diskstats.push(DiskDetail {
                     disk_name: row[1..].to_string(),
-                    reads_completed: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_reads_completed_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap(),
-                    writes_completed: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_writes_completed_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap(),
-                    discards_completed: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_discards_completed_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap_or_default(),
-                    reads_merged: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_reads_merged_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap(),
-                    writes_merged: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_writes_merged_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap(),
-                    discards_merged: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_discards_merged_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap_or_default(),
-                    reads_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_read_bytes_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap(),
-                    writes_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_written_bytes_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap(),
-                    discards_sectors: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_discarded_sectors_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap_or_default(),
-                    reads_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_read_time_seconds_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap(),
-                    writes_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_write_time_seconds_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap(),
-                    discards_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_discard_time_seconds_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap_or_default(),
-                    total_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_io_time_seconds_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap(),
-                    queue: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_io_time_weighted_seconds_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).nth(0).unwrap(),
+                    reads_completed: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_reads_completed_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    writes_completed: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_writes_completed_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    discards_completed: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_discards_completed_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                    reads_merged: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_reads_merged_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    writes_merged: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_writes_merged_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    discards_merged: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_discards_merged_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                    reads_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_read_bytes_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    writes_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_written_bytes_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    discards_sectors: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_discarded_sectors_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                    reads_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_read_time_seconds_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    writes_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_write_time_seconds_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    discards_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_discard_time_seconds_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                    total_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_io_time_seconds_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    queue: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_disk_io_time_weighted_seconds_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
                 });
             }
             details.push(
                 DiskHost {
                     hostname_port: hostname_port.to_string(),
-                    timestamp: node_exporter_vector.iter().map(|x| x.node_exporter_timestamp).nth(0).unwrap(),
+                    timestamp: node_exporter_vector.iter().map(|x| x.node_exporter_timestamp).next().unwrap(),
                     diskdetail: diskstats,
+                    node_boot_time: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_boot_time_seconds").map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                }
+            );
+        }
+    }
+    details
+}
+
+pub fn network_details(
+    values: &HashMap<String, Vec<NodeExporterValues>>
+) -> Vec<NetworkHost>
+{
+    let mut details: Vec<NetworkHost> = Vec::new();
+    for (hostname_port, node_exporter_vector) in values {
+        if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_network_receive_bytes_total").count() > 0 {
+            let mut networkstats: Vec<NetworkDetail> = Vec::new();
+            // skip the loopback device and the virtual interfaces of containers.
+            for row in node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_network_receive_bytes_total").filter(|r| !(r.node_exporter_labels == "_lo" || r.node_exporter_labels.starts_with("_veth") || r.node_exporter_labels.starts_with("_docker"))).map(|x| x.node_exporter_labels.clone()) {
+                networkstats.push(NetworkDetail {
+                    interface_name: row[1..].to_string(),
+                    receive_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_network_receive_bytes_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    transmit_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_network_transmit_bytes_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    receive_packets: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_network_receive_packets_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    transmit_packets: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_network_transmit_packets_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    receive_errs: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_network_receive_errs_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    transmit_errs: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_network_transmit_errs_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    receive_drop: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_network_receive_drop_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    transmit_drop: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_network_transmit_drop_total" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    // the speed is not available for virtual interfaces, or is reported as -1.
+                    speed_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_network_speed_bytes" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                });
+            }
+            details.push(
+                NetworkHost {
+                    hostname_port: hostname_port.to_string(),
+                    timestamp: node_exporter_vector.iter().map(|x| x.node_exporter_timestamp).next().unwrap(),
+                    networkdetail: networkstats,
+                }
+            );
+        }
+    }
+    details
+}
+
+pub fn filesystem_details(
+    values: &HashMap<String, Vec<NodeExporterValues>>
+) -> Vec<FilesystemHost>
+{
+    let mut details: Vec<FilesystemHost> = Vec::new();
+    for (hostname_port, node_exporter_vector) in values {
+        if node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_filesystem_size_bytes").count() > 0 {
+            let mut filesystemstats: Vec<FilesystemDetail> = Vec::new();
+            // the label is _fstype device mountpoint, see parse_node_exporter.
+            // skip memory based and container overlay filesystems.
+            for row in node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_filesystem_size_bytes").filter(|r| !(r.node_exporter_labels.starts_with("_tmpfs ") || r.node_exporter_labels.starts_with("_devtmpfs ") || r.node_exporter_labels.starts_with("_overlay "))).map(|x| x.node_exporter_labels.clone()) {
+                let (fstype, device_mountpoint) = row[1..].split_once(' ').unwrap_or_default();
+                let (device, mountpoint) = device_mountpoint.split_once(' ').unwrap_or_default();
+                filesystemstats.push(FilesystemDetail {
+                    mountpoint: mountpoint.to_string(),
+                    device: device.to_string(),
+                    fstype: fstype.to_string(),
+                    size_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_filesystem_size_bytes" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    avail_bytes: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_filesystem_avail_bytes" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap(),
+                    files: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_filesystem_files" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                    files_free: node_exporter_vector.iter().filter(|r| r.node_exporter_name == "node_filesystem_files_free" && r.node_exporter_labels == row).map(|x| x.node_exporter_value).next().unwrap_or_default(),
+                });
+            }
+            details.push(
+                FilesystemHost {
+                    hostname_port: hostname_port.to_string(),
+                    timestamp: node_exporter_vector.iter().map(|x| x.node_exporter_timestamp).next().unwrap(),
+                    filesystemdetail: filesystemstats,
                 }
             );
         }
@@ -867,48 +2539,109 @@ pub fn disk_details(
     details
 }
 
+// a counter that is lower than its previous value means the counters are reset, such as by a restart or a reboot.
+// the counters are given as pairs of the new value and the value of the row.
+fn counters_reset(
+    pairs: &[(f64, f64)],
+) -> bool {
+    pairs.iter().any(|(new, previous)| new < previous)
+}
+
+// the yugabyte counters are summed over the tablets or label sets of a server, so a sum goes down without a restart when
+// a tablet moves to another server or a table is dropped. a lower sum is therefore taken as no change, instead of a reset.
+fn summed_counter_diff(
+    new: f64,
+    previous: f64,
+    time_difference: f64,
+) -> f64 {
+    (new - previous).max(0.) / time_difference
+}
+
+// a changed boot time or process start time means the counters are reset, even if they have grown past their previous values.
+// the boot time is calculated by the kernel from the clock and the uptime, so it can move a little without a reboot.
+fn start_time_changed(
+    new: f64,
+    previous: f64,
+) -> bool {
+    (new - previous).abs() > 5.
+}
+
+// a row that is reset is started over, so no rate is calculated over the reset.
+fn remove_reset_row<T>(
+    presentation: &mut BTreeMap<String, T>,
+    key: &str,
+    reset: impl FnOnce(&T) -> bool,
+) -> bool {
+    let reset = presentation.get(key).map(reset).unwrap_or(false);
+    if reset {
+        presentation.remove(key);
+    }
+    reset
+}
+
 pub fn diff_yugabyte_details(
     values: Vec<YugabyteIODetails>,
     yugabyte_presentation: &mut BTreeMap<String, YBIOPresentation>,
 ) {
     for yugabyte_details in values {
+        let reset = remove_reset_row(yugabyte_presentation, &yugabyte_details.hostname_port, |row| {
+            start_time_changed(yugabyte_details.process_start_time, row.process_start_time)
+        });
         match yugabyte_presentation.get_mut( &yugabyte_details.hostname_port) {
             Some(row) => {
                let time_difference = yugabyte_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.;
                 *row = YBIOPresentation {
                     timestamp: yugabyte_details.timestamp,
-                    glog_messages_info_diff: (yugabyte_details.glog_messages_info - row.glog_messages_info_counter)/time_difference,
+                    glog_messages_info_diff: summed_counter_diff(yugabyte_details.glog_messages_info, row.glog_messages_info_counter, time_difference),
                     glog_messages_info_counter: yugabyte_details.glog_messages_info,
-                    glog_messages_prio_diff: (yugabyte_details.glog_messages_prio - row.glog_messages_prio_counter)/time_difference,
+                    glog_messages_prio_diff: summed_counter_diff(yugabyte_details.glog_messages_prio, row.glog_messages_prio_counter, time_difference),
                     glog_messages_prio_counter: yugabyte_details.glog_messages_prio,
-                    log_bytes_logged_diff: (yugabyte_details.log_bytes_logged - row.log_bytes_logged_counter)/time_difference,
+                    log_bytes_logged_diff: summed_counter_diff(yugabyte_details.log_bytes_logged, row.log_bytes_logged_counter, time_difference),
                     log_bytes_logged_counter: yugabyte_details.log_bytes_logged,
-                    log_reader_bytes_read_diff: (yugabyte_details.log_reader_bytes_read - row.log_reader_bytes_read_counter)/time_difference,
+                    log_reader_bytes_read_diff: summed_counter_diff(yugabyte_details.log_reader_bytes_read, row.log_reader_bytes_read_counter, time_difference),
                     log_reader_bytes_read_counter: yugabyte_details.log_reader_bytes_read,
-                    log_sync_latency_count_diff: (yugabyte_details.log_sync_latency_count - row.log_sync_latency_count_counter)/time_difference,
+                    log_sync_latency_count_diff: summed_counter_diff(yugabyte_details.log_sync_latency_count, row.log_sync_latency_count_counter, time_difference),
                     log_sync_latency_count_counter: yugabyte_details.log_sync_latency_count,
-                    log_sync_latency_sum_diff: (yugabyte_details.log_sync_latency_sum - row.log_sync_latency_sum_counter)/time_difference,
+                    log_sync_latency_sum_diff: summed_counter_diff(yugabyte_details.log_sync_latency_sum, row.log_sync_latency_sum_counter, time_difference),
                     log_sync_latency_sum_counter: yugabyte_details.log_sync_latency_sum,
-                    log_append_latency_count_diff: (yugabyte_details.log_append_latency_count - row.log_append_latency_count_counter)/time_difference,
+                    log_append_latency_count_diff: summed_counter_diff(yugabyte_details.log_append_latency_count, row.log_append_latency_count_counter, time_difference),
                     log_append_latency_count_counter: yugabyte_details.log_append_latency_count,
-                    log_append_latency_sum_diff: (yugabyte_details.log_append_latency_sum - row.log_append_latency_sum_counter)/time_difference,
+                    log_append_latency_sum_diff: summed_counter_diff(yugabyte_details.log_append_latency_sum, row.log_append_latency_sum_counter, time_difference),
                     log_append_latency_sum_counter: yugabyte_details.log_append_latency_sum,
-                    log_cache_disk_reads_diff: (yugabyte_details.log_cache_disk_reads - row.log_cache_disk_reads_counter)/time_difference,
+                    log_cache_disk_reads_diff: summed_counter_diff(yugabyte_details.log_cache_disk_reads, row.log_cache_disk_reads_counter, time_difference),
                     log_cache_disk_reads_counter: yugabyte_details.log_cache_disk_reads,
-                    rocksdb_flush_write_bytes_diff: (yugabyte_details.rocksdb_flush_write_bytes - row.rocksdb_flush_write_bytes_counter)/time_difference,
+                    rocksdb_flush_write_bytes_diff: summed_counter_diff(yugabyte_details.rocksdb_flush_write_bytes, row.rocksdb_flush_write_bytes_counter, time_difference),
                     rocksdb_flush_write_bytes_counter: yugabyte_details.rocksdb_flush_write_bytes,
-                    rocksdb_compact_read_bytes_diff: (yugabyte_details.rocksdb_compact_read_bytes - row.rocksdb_compact_read_bytes_counter)/time_difference,
+                    rocksdb_compact_read_bytes_diff: summed_counter_diff(yugabyte_details.rocksdb_compact_read_bytes, row.rocksdb_compact_read_bytes_counter, time_difference),
                     rocksdb_compact_read_bytes_counter: yugabyte_details.rocksdb_compact_read_bytes,
-                    rocksdb_compact_write_bytes_diff: (yugabyte_details.rocksdb_compact_write_bytes - row.rocksdb_compact_write_bytes_counter)/time_difference,
+                    rocksdb_compact_write_bytes_diff: summed_counter_diff(yugabyte_details.rocksdb_compact_write_bytes, row.rocksdb_compact_write_bytes_counter, time_difference),
                     rocksdb_compact_write_bytes_counter: yugabyte_details.rocksdb_compact_write_bytes,
-                    rocksdb_write_raw_block_micros_count_diff: (yugabyte_details.rocksdb_write_raw_block_micros_count - row.rocksdb_write_raw_block_micros_count_counter)/time_difference,
+                    rocksdb_write_raw_block_micros_count_diff: summed_counter_diff(yugabyte_details.rocksdb_write_raw_block_micros_count, row.rocksdb_write_raw_block_micros_count_counter, time_difference),
                     rocksdb_write_raw_block_micros_count_counter: yugabyte_details.rocksdb_write_raw_block_micros_count,
-                    rocksdb_write_raw_block_micros_sum_diff: (yugabyte_details.rocksdb_write_raw_block_micros_sum - row.rocksdb_write_raw_block_micros_sum_counter)/time_difference,
+                    rocksdb_write_raw_block_micros_sum_diff: summed_counter_diff(yugabyte_details.rocksdb_write_raw_block_micros_sum, row.rocksdb_write_raw_block_micros_sum_counter, time_difference),
                     rocksdb_write_raw_block_micros_sum_counter: yugabyte_details.rocksdb_write_raw_block_micros_sum,
-                    rocksdb_sst_read_micros_count_diff: (yugabyte_details.rocksdb_sst_read_micros_count - row.rocksdb_sst_read_micros_count_counter)/time_difference,
+                    rocksdb_sst_read_micros_count_diff: summed_counter_diff(yugabyte_details.rocksdb_sst_read_micros_count, row.rocksdb_sst_read_micros_count_counter, time_difference),
                     rocksdb_sst_read_micros_count_counter: yugabyte_details.rocksdb_sst_read_micros_count,
-                    rocksdb_sst_read_micros_sum_diff: (yugabyte_details.rocksdb_sst_read_micros_sum - row.rocksdb_sst_read_micros_sum_counter)/time_difference,
+                    rocksdb_sst_read_micros_sum_diff: summed_counter_diff(yugabyte_details.rocksdb_sst_read_micros_sum, row.rocksdb_sst_read_micros_sum_counter, time_difference),
                     rocksdb_sst_read_micros_sum_counter:yugabyte_details.rocksdb_sst_read_micros_sum,
+                    log_sync_latency_p50: latency_percentile(&yugabyte_details.log_sync_latency_buckets, &row.log_sync_latency_buckets_counter, &yugabyte_details.log_sync_latency_quantiles, 0.50),
+                    log_sync_latency_p95: latency_percentile(&yugabyte_details.log_sync_latency_buckets, &row.log_sync_latency_buckets_counter, &yugabyte_details.log_sync_latency_quantiles, 0.95),
+                    log_sync_latency_p99: latency_percentile(&yugabyte_details.log_sync_latency_buckets, &row.log_sync_latency_buckets_counter, &yugabyte_details.log_sync_latency_quantiles, 0.99),
+                    log_sync_latency_buckets_counter: yugabyte_details.log_sync_latency_buckets,
+                    log_append_latency_p50: latency_percentile(&yugabyte_details.log_append_latency_buckets, &row.log_append_latency_buckets_counter, &yugabyte_details.log_append_latency_quantiles, 0.50),
+                    log_append_latency_p95: latency_percentile(&yugabyte_details.log_append_latency_buckets, &row.log_append_latency_buckets_counter, &yugabyte_details.log_append_latency_quantiles, 0.95),
+                    log_append_latency_p99: latency_percentile(&yugabyte_details.log_append_latency_buckets, &row.log_append_latency_buckets_counter, &yugabyte_details.log_append_latency_quantiles, 0.99),
+                    log_append_latency_buckets_counter: yugabyte_details.log_append_latency_buckets,
+                    rocksdb_write_raw_block_micros_p50: latency_percentile(&yugabyte_details.rocksdb_write_raw_block_micros_buckets, &row.rocksdb_write_raw_block_micros_buckets_counter, &yugabyte_details.rocksdb_write_raw_block_micros_quantiles, 0.50),
+                    rocksdb_write_raw_block_micros_p95: latency_percentile(&yugabyte_details.rocksdb_write_raw_block_micros_buckets, &row.rocksdb_write_raw_block_micros_buckets_counter, &yugabyte_details.rocksdb_write_raw_block_micros_quantiles, 0.95),
+                    rocksdb_write_raw_block_micros_p99: latency_percentile(&yugabyte_details.rocksdb_write_raw_block_micros_buckets, &row.rocksdb_write_raw_block_micros_buckets_counter, &yugabyte_details.rocksdb_write_raw_block_micros_quantiles, 0.99),
+                    rocksdb_write_raw_block_micros_buckets_counter: yugabyte_details.rocksdb_write_raw_block_micros_buckets,
+                    rocksdb_sst_read_micros_p50: latency_percentile(&yugabyte_details.rocksdb_sst_read_micros_buckets, &row.rocksdb_sst_read_micros_buckets_counter, &yugabyte_details.rocksdb_sst_read_micros_quantiles, 0.50),
+                    rocksdb_sst_read_micros_p95: latency_percentile(&yugabyte_details.rocksdb_sst_read_micros_buckets, &row.rocksdb_sst_read_micros_buckets_counter, &yugabyte_details.rocksdb_sst_read_micros_quantiles, 0.95),
+                    rocksdb_sst_read_micros_p99: latency_percentile(&yugabyte_details.rocksdb_sst_read_micros_buckets, &row.rocksdb_sst_read_micros_buckets_counter, &yugabyte_details.rocksdb_sst_read_micros_quantiles, 0.99),
+                    rocksdb_sst_read_micros_buckets_counter: yugabyte_details.rocksdb_sst_read_micros_buckets,
+                    process_start_time: yugabyte_details.process_start_time,
+                    reset: false,
                 }
             },
             None => {
@@ -946,6 +2679,328 @@ pub fn diff_yugabyte_details(
                     rocksdb_sst_read_micros_count_counter: yugabyte_details.rocksdb_sst_read_micros_count,
                     rocksdb_sst_read_micros_sum_diff: 0.,
                     rocksdb_sst_read_micros_sum_counter:yugabyte_details.rocksdb_sst_read_micros_sum,
+                    log_sync_latency_p50: 0.,
+                    log_sync_latency_p95: 0.,
+                    log_sync_latency_p99: 0.,
+                    log_sync_latency_buckets_counter: yugabyte_details.log_sync_latency_buckets,
+                    log_append_latency_p50: 0.,
+                    log_append_latency_p95: 0.,
+                    log_append_latency_p99: 0.,
+                    log_append_latency_buckets_counter: yugabyte_details.log_append_latency_buckets,
+                    rocksdb_write_raw_block_micros_p50: 0.,
+                    rocksdb_write_raw_block_micros_p95: 0.,
+                    rocksdb_write_raw_block_micros_p99: 0.,
+                    rocksdb_write_raw_block_micros_buckets_counter: yugabyte_details.rocksdb_write_raw_block_micros_buckets,
+                    rocksdb_sst_read_micros_p50: 0.,
+                    rocksdb_sst_read_micros_p95: 0.,
+                    rocksdb_sst_read_micros_p99: 0.,
+                    rocksdb_sst_read_micros_buckets_counter: yugabyte_details.rocksdb_sst_read_micros_buckets,
+                    process_start_time: yugabyte_details.process_start_time,
+                    reset,
+                });
+            },
+        }
+    }
+}
+
+pub fn yugabyte_cluster_total(
+    yugabyte_presentation: &BTreeMap<String, YBIOPresentation>,
+    unreachable: &HashMap<String, String>,
+) -> Option<YBIOPresentation> {
+    // the cluster total is the sum of the rates of all servers. because the latency sums are summed with their counts,
+    // the latency calculated from the total is weighted by the counts of every server. percentiles cannot be added up, so
+    // these are left zero. rows that are reset or unreachable are left out.
+    let rows: Vec<&YBIOPresentation> = yugabyte_presentation
+        .iter()
+        .filter(|(key, row)| !row.reset && !unreachable.contains_key(key.split_whitespace().next().unwrap()))
+        .map(|(_, row)| row)
+        .collect();
+    // a total of a single server is the server itself, so it is not shown.
+    if rows.len() < 2 { return None };
+    let sum = |value: fn(&YBIOPresentation) -> f64| rows.iter().map(|row| value(row)).sum::<f64>();
+    Some(YBIOPresentation {
+        timestamp: rows.iter().map(|row| row.timestamp).max().unwrap(),
+        glog_messages_info_diff: sum(|row| row.glog_messages_info_diff),
+        glog_messages_info_counter: 0.,
+        glog_messages_prio_diff: sum(|row| row.glog_messages_prio_diff),
+        glog_messages_prio_counter: 0.,
+        log_bytes_logged_diff: sum(|row| row.log_bytes_logged_diff),
+        log_bytes_logged_counter: 0.,
+        log_reader_bytes_read_diff: sum(|row| row.log_reader_bytes_read_diff),
+        log_reader_bytes_read_counter: 0.,
+        log_sync_latency_count_diff: sum(|row| row.log_sync_latency_count_diff),
+        log_sync_latency_count_counter: 0.,
+        log_sync_latency_sum_diff: sum(|row| row.log_sync_latency_sum_diff),
+        log_sync_latency_sum_counter: 0.,
+        log_append_latency_count_diff: sum(|row| row.log_append_latency_count_diff),
+        log_append_latency_count_counter: 0.,
+        log_append_latency_sum_diff: sum(|row| row.log_append_latency_sum_diff),
+        log_append_latency_sum_counter: 0.,
+        log_cache_disk_reads_diff: sum(|row| row.log_cache_disk_reads_diff),
+        log_cache_disk_reads_counter: 0.,
+        rocksdb_flush_write_bytes_diff: sum(|row| row.rocksdb_flush_write_bytes_diff),
+        rocksdb_flush_write_bytes_counter: 0.,
+        rocksdb_compact_read_bytes_diff: sum(|row| row.rocksdb_compact_read_bytes_diff),
+        rocksdb_compact_read_bytes_counter: 0.,
+        rocksdb_compact_write_bytes_diff: sum(|row| row.rocksdb_compact_write_bytes_diff),
+        rocksdb_compact_write_bytes_counter: 0.,
+        rocksdb_write_raw_block_micros_count_diff: sum(|row| row.rocksdb_write_raw_block_micros_count_diff),
+        rocksdb_write_raw_block_micros_count_counter: 0.,
+        rocksdb_write_raw_block_micros_sum_diff: sum(|row| row.rocksdb_write_raw_block_micros_sum_diff),
+        rocksdb_write_raw_block_micros_sum_counter: 0.,
+        rocksdb_sst_read_micros_count_diff: sum(|row| row.rocksdb_sst_read_micros_count_diff),
+        rocksdb_sst_read_micros_count_counter: 0.,
+        rocksdb_sst_read_micros_sum_diff: sum(|row| row.rocksdb_sst_read_micros_sum_diff),
+        rocksdb_sst_read_micros_sum_counter: 0.,
+        log_sync_latency_buckets_counter: Vec::new(),
+        log_sync_latency_p50: 0.,
+        log_sync_latency_p95: 0.,
+        log_sync_latency_p99: 0.,
+        log_append_latency_buckets_counter: Vec::new(),
+        log_append_latency_p50: 0.,
+        log_append_latency_p95: 0.,
+        log_append_latency_p99: 0.,
+        rocksdb_write_raw_block_micros_buckets_counter: Vec::new(),
+        rocksdb_write_raw_block_micros_p50: 0.,
+        rocksdb_write_raw_block_micros_p95: 0.,
+        rocksdb_write_raw_block_micros_p99: 0.,
+        rocksdb_sst_read_micros_buckets_counter: Vec::new(),
+        rocksdb_sst_read_micros_p50: 0.,
+        rocksdb_sst_read_micros_p95: 0.,
+        rocksdb_sst_read_micros_p99: 0.,
+        process_start_time: 0.,
+        reset: false,
+    })
+}
+
+pub fn diff_yugabyte_rpc_details(
+    values: Vec<YugabyteRpcDetails>,
+    yugabyte_rpc_presentation: &mut BTreeMap<String, YBRpcPresentation>,
+) {
+    for yugabyte_rpc_details in values {
+        let reset = remove_reset_row(yugabyte_rpc_presentation, &yugabyte_rpc_details.hostname_port, |row| {
+            start_time_changed(yugabyte_rpc_details.process_start_time, row.process_start_time)
+        });
+        match yugabyte_rpc_presentation.get_mut( &yugabyte_rpc_details.hostname_port) {
+            Some(row) => {
+                let time_difference = yugabyte_rpc_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.;
+                *row = YBRpcPresentation {
+                    timestamp: yugabyte_rpc_details.timestamp,
+                    tserver_read_count_diff: summed_counter_diff(yugabyte_rpc_details.tserver_read_count, row.tserver_read_count_counter, time_difference),
+                    tserver_read_count_counter: yugabyte_rpc_details.tserver_read_count,
+                    tserver_read_sum_diff: summed_counter_diff(yugabyte_rpc_details.tserver_read_sum, row.tserver_read_sum_counter, time_difference),
+                    tserver_read_sum_counter: yugabyte_rpc_details.tserver_read_sum,
+                    tserver_write_count_diff: summed_counter_diff(yugabyte_rpc_details.tserver_write_count, row.tserver_write_count_counter, time_difference),
+                    tserver_write_count_counter: yugabyte_rpc_details.tserver_write_count,
+                    tserver_write_sum_diff: summed_counter_diff(yugabyte_rpc_details.tserver_write_sum, row.tserver_write_sum_counter, time_difference),
+                    tserver_write_sum_counter: yugabyte_rpc_details.tserver_write_sum,
+                    consensus_update_count_diff: summed_counter_diff(yugabyte_rpc_details.consensus_update_count, row.consensus_update_count_counter, time_difference),
+                    consensus_update_count_counter: yugabyte_rpc_details.consensus_update_count,
+                    consensus_update_sum_diff: summed_counter_diff(yugabyte_rpc_details.consensus_update_sum, row.consensus_update_sum_counter, time_difference),
+                    consensus_update_sum_counter: yugabyte_rpc_details.consensus_update_sum,
+                    rpc_incoming_queue_time_count_diff: summed_counter_diff(yugabyte_rpc_details.rpc_incoming_queue_time_count, row.rpc_incoming_queue_time_count_counter, time_difference),
+                    rpc_incoming_queue_time_count_counter: yugabyte_rpc_details.rpc_incoming_queue_time_count,
+                    rpc_incoming_queue_time_sum_diff: summed_counter_diff(yugabyte_rpc_details.rpc_incoming_queue_time_sum, row.rpc_incoming_queue_time_sum_counter, time_difference),
+                    rpc_incoming_queue_time_sum_counter: yugabyte_rpc_details.rpc_incoming_queue_time_sum,
+                    tserver_read_p50: latency_percentile(&yugabyte_rpc_details.tserver_read_buckets, &row.tserver_read_buckets_counter, &yugabyte_rpc_details.tserver_read_quantiles, 0.50),
+                    tserver_read_p95: latency_percentile(&yugabyte_rpc_details.tserver_read_buckets, &row.tserver_read_buckets_counter, &yugabyte_rpc_details.tserver_read_quantiles, 0.95),
+                    tserver_read_p99: latency_percentile(&yugabyte_rpc_details.tserver_read_buckets, &row.tserver_read_buckets_counter, &yugabyte_rpc_details.tserver_read_quantiles, 0.99),
+                    tserver_read_buckets_counter: yugabyte_rpc_details.tserver_read_buckets,
+                    tserver_write_p50: latency_percentile(&yugabyte_rpc_details.tserver_write_buckets, &row.tserver_write_buckets_counter, &yugabyte_rpc_details.tserver_write_quantiles, 0.50),
+                    tserver_write_p95: latency_percentile(&yugabyte_rpc_details.tserver_write_buckets, &row.tserver_write_buckets_counter, &yugabyte_rpc_details.tserver_write_quantiles, 0.95),
+                    tserver_write_p99: latency_percentile(&yugabyte_rpc_details.tserver_write_buckets, &row.tserver_write_buckets_counter, &yugabyte_rpc_details.tserver_write_quantiles, 0.99),
+                    tserver_write_buckets_counter: yugabyte_rpc_details.tserver_write_buckets,
+                    consensus_update_p50: latency_percentile(&yugabyte_rpc_details.consensus_update_buckets, &row.consensus_update_buckets_counter, &yugabyte_rpc_details.consensus_update_quantiles, 0.50),
+                    consensus_update_p95: latency_percentile(&yugabyte_rpc_details.consensus_update_buckets, &row.consensus_update_buckets_counter, &yugabyte_rpc_details.consensus_update_quantiles, 0.95),
+                    consensus_update_p99: latency_percentile(&yugabyte_rpc_details.consensus_update_buckets, &row.consensus_update_buckets_counter, &yugabyte_rpc_details.consensus_update_quantiles, 0.99),
+                    consensus_update_buckets_counter: yugabyte_rpc_details.consensus_update_buckets,
+                    rpc_incoming_queue_time_p50: latency_percentile(&yugabyte_rpc_details.rpc_incoming_queue_time_buckets, &row.rpc_incoming_queue_time_buckets_counter, &yugabyte_rpc_details.rpc_incoming_queue_time_quantiles, 0.50),
+                    rpc_incoming_queue_time_p95: latency_percentile(&yugabyte_rpc_details.rpc_incoming_queue_time_buckets, &row.rpc_incoming_queue_time_buckets_counter, &yugabyte_rpc_details.rpc_incoming_queue_time_quantiles, 0.95),
+                    rpc_incoming_queue_time_p99: latency_percentile(&yugabyte_rpc_details.rpc_incoming_queue_time_buckets, &row.rpc_incoming_queue_time_buckets_counter, &yugabyte_rpc_details.rpc_incoming_queue_time_quantiles, 0.99),
+                    rpc_incoming_queue_time_buckets_counter: yugabyte_rpc_details.rpc_incoming_queue_time_buckets,
+                    process_start_time: yugabyte_rpc_details.process_start_time,
+                    reset: false,
+                }
+            },
+            None => {
+                yugabyte_rpc_presentation.insert( yugabyte_rpc_details.hostname_port.to_string(), YBRpcPresentation {
+                    timestamp: yugabyte_rpc_details.timestamp,
+                    tserver_read_count_diff: 0.0,
+                    tserver_read_count_counter: yugabyte_rpc_details.tserver_read_count,
+                    tserver_read_sum_diff: 0.0,
+                    tserver_read_sum_counter: yugabyte_rpc_details.tserver_read_sum,
+                    tserver_write_count_diff: 0.0,
+                    tserver_write_count_counter: yugabyte_rpc_details.tserver_write_count,
+                    tserver_write_sum_diff: 0.0,
+                    tserver_write_sum_counter: yugabyte_rpc_details.tserver_write_sum,
+                    consensus_update_count_diff: 0.0,
+                    consensus_update_count_counter: yugabyte_rpc_details.consensus_update_count,
+                    consensus_update_sum_diff: 0.0,
+                    consensus_update_sum_counter: yugabyte_rpc_details.consensus_update_sum,
+                    rpc_incoming_queue_time_count_diff: 0.0,
+                    rpc_incoming_queue_time_count_counter: yugabyte_rpc_details.rpc_incoming_queue_time_count,
+                    rpc_incoming_queue_time_sum_diff: 0.0,
+                    rpc_incoming_queue_time_sum_counter: yugabyte_rpc_details.rpc_incoming_queue_time_sum,
+                    tserver_read_p50: 0.,
+                    tserver_read_p95: 0.,
+                    tserver_read_p99: 0.,
+                    tserver_read_buckets_counter: yugabyte_rpc_details.tserver_read_buckets,
+                    tserver_write_p50: 0.,
+                    tserver_write_p95: 0.,
+                    tserver_write_p99: 0.,
+                    tserver_write_buckets_counter: yugabyte_rpc_details.tserver_write_buckets,
+                    consensus_update_p50: 0.,
+                    consensus_update_p95: 0.,
+                    consensus_update_p99: 0.,
+                    consensus_update_buckets_counter: yugabyte_rpc_details.consensus_update_buckets,
+                    rpc_incoming_queue_time_p50: 0.,
+                    rpc_incoming_queue_time_p95: 0.,
+                    rpc_incoming_queue_time_p99: 0.,
+                    rpc_incoming_queue_time_buckets_counter: yugabyte_rpc_details.rpc_incoming_queue_time_buckets,
+                    process_start_time: yugabyte_rpc_details.process_start_time,
+                    reset,
+                });
+            },
+        }
+    }
+}
+
+pub fn diff_yugabyte_ysql_details(
+    values: Vec<YugabyteYsqlDetails>,
+    yugabyte_ysql_presentation: &mut BTreeMap<String, YBYsqlPresentation>,
+) {
+    for yugabyte_ysql_details in values {
+        let reset = remove_reset_row(yugabyte_ysql_presentation, &yugabyte_ysql_details.hostname_port, |row| {
+            start_time_changed(yugabyte_ysql_details.process_start_time, row.process_start_time)
+        });
+        match yugabyte_ysql_presentation.get_mut( &yugabyte_ysql_details.hostname_port) {
+            Some(row) => {
+                let time_difference = yugabyte_ysql_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.;
+                *row = YBYsqlPresentation {
+                    timestamp: yugabyte_ysql_details.timestamp,
+                    select_count_diff: summed_counter_diff(yugabyte_ysql_details.select_count, row.select_count_counter, time_difference),
+                    select_count_counter: yugabyte_ysql_details.select_count,
+                    select_sum_diff: summed_counter_diff(yugabyte_ysql_details.select_sum, row.select_sum_counter, time_difference),
+                    select_sum_counter: yugabyte_ysql_details.select_sum,
+                    insert_count_diff: summed_counter_diff(yugabyte_ysql_details.insert_count, row.insert_count_counter, time_difference),
+                    insert_count_counter: yugabyte_ysql_details.insert_count,
+                    insert_sum_diff: summed_counter_diff(yugabyte_ysql_details.insert_sum, row.insert_sum_counter, time_difference),
+                    insert_sum_counter: yugabyte_ysql_details.insert_sum,
+                    update_count_diff: summed_counter_diff(yugabyte_ysql_details.update_count, row.update_count_counter, time_difference),
+                    update_count_counter: yugabyte_ysql_details.update_count,
+                    update_sum_diff: summed_counter_diff(yugabyte_ysql_details.update_sum, row.update_sum_counter, time_difference),
+                    update_sum_counter: yugabyte_ysql_details.update_sum,
+                    delete_count_diff: summed_counter_diff(yugabyte_ysql_details.delete_count, row.delete_count_counter, time_difference),
+                    delete_count_counter: yugabyte_ysql_details.delete_count,
+                    delete_sum_diff: summed_counter_diff(yugabyte_ysql_details.delete_sum, row.delete_sum_counter, time_difference),
+                    delete_sum_counter: yugabyte_ysql_details.delete_sum,
+                    transactions_count_diff: summed_counter_diff(yugabyte_ysql_details.transactions_count, row.transactions_count_counter, time_difference),
+                    transactions_count_counter: yugabyte_ysql_details.transactions_count,
+                    transactions_sum_diff: summed_counter_diff(yugabyte_ysql_details.transactions_sum, row.transactions_sum_counter, time_difference),
+                    transactions_sum_counter: yugabyte_ysql_details.transactions_sum,
+                    catalog_cache_misses_count_diff: summed_counter_diff(yugabyte_ysql_details.catalog_cache_misses_count, row.catalog_cache_misses_count_counter, time_difference),
+                    catalog_cache_misses_count_counter: yugabyte_ysql_details.catalog_cache_misses_count,
+                    catalog_cache_misses_sum_diff: summed_counter_diff(yugabyte_ysql_details.catalog_cache_misses_sum, row.catalog_cache_misses_sum_counter, time_difference),
+                    catalog_cache_misses_sum_counter: yugabyte_ysql_details.catalog_cache_misses_sum,
+                    process_start_time: yugabyte_ysql_details.process_start_time,
+                    reset: false,
+                }
+            },
+            None => {
+                yugabyte_ysql_presentation.insert( yugabyte_ysql_details.hostname_port.to_string(), YBYsqlPresentation {
+                    timestamp: yugabyte_ysql_details.timestamp,
+                    select_count_diff: 0.0,
+                    select_count_counter: yugabyte_ysql_details.select_count,
+                    select_sum_diff: 0.0,
+                    select_sum_counter: yugabyte_ysql_details.select_sum,
+                    insert_count_diff: 0.0,
+                    insert_count_counter: yugabyte_ysql_details.insert_count,
+                    insert_sum_diff: 0.0,
+                    insert_sum_counter: yugabyte_ysql_details.insert_sum,
+                    update_count_diff: 0.0,
+                    update_count_counter: yugabyte_ysql_details.update_count,
+                    update_sum_diff: 0.0,
+                    update_sum_counter: yugabyte_ysql_details.update_sum,
+                    delete_count_diff: 0.0,
+                    delete_count_counter: yugabyte_ysql_details.delete_count,
+                    delete_sum_diff: 0.0,
+                    delete_sum_counter: yugabyte_ysql_details.delete_sum,
+                    transactions_count_diff: 0.0,
+                    transactions_count_counter: yugabyte_ysql_details.transactions_count,
+                    transactions_sum_diff: 0.0,
+                    transactions_sum_counter: yugabyte_ysql_details.transactions_sum,
+                    catalog_cache_misses_count_diff: 0.0,
+                    catalog_cache_misses_count_counter: yugabyte_ysql_details.catalog_cache_misses_count,
+                    catalog_cache_misses_sum_diff: 0.0,
+                    catalog_cache_misses_sum_counter: yugabyte_ysql_details.catalog_cache_misses_sum,
+                    process_start_time: yugabyte_ysql_details.process_start_time,
+                    reset,
+                });
+            },
+        }
+    }
+}
+
+pub fn diff_yugabyte_ycql_details(
+    values: Vec<YugabyteYcqlDetails>,
+    yugabyte_ycql_presentation: &mut BTreeMap<String, YBYcqlPresentation>,
+) {
+    for yugabyte_ycql_details in values {
+        let reset = remove_reset_row(yugabyte_ycql_presentation, &yugabyte_ycql_details.hostname_port, |row| {
+            start_time_changed(yugabyte_ycql_details.process_start_time, row.process_start_time)
+        });
+        match yugabyte_ycql_presentation.get_mut( &yugabyte_ycql_details.hostname_port) {
+            Some(row) => {
+                let time_difference = yugabyte_ycql_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.;
+                *row = YBYcqlPresentation {
+                    timestamp: yugabyte_ycql_details.timestamp,
+                    select_count_diff: summed_counter_diff(yugabyte_ycql_details.select_count, row.select_count_counter, time_difference),
+                    select_count_counter: yugabyte_ycql_details.select_count,
+                    select_sum_diff: summed_counter_diff(yugabyte_ycql_details.select_sum, row.select_sum_counter, time_difference),
+                    select_sum_counter: yugabyte_ycql_details.select_sum,
+                    insert_count_diff: summed_counter_diff(yugabyte_ycql_details.insert_count, row.insert_count_counter, time_difference),
+                    insert_count_counter: yugabyte_ycql_details.insert_count,
+                    insert_sum_diff: summed_counter_diff(yugabyte_ycql_details.insert_sum, row.insert_sum_counter, time_difference),
+                    insert_sum_counter: yugabyte_ycql_details.insert_sum,
+                    execute_request_count_diff: summed_counter_diff(yugabyte_ycql_details.execute_request_count, row.execute_request_count_counter, time_difference),
+                    execute_request_count_counter: yugabyte_ycql_details.execute_request_count,
+                    execute_request_sum_diff: summed_counter_diff(yugabyte_ycql_details.execute_request_sum, row.execute_request_sum_counter, time_difference),
+                    execute_request_sum_counter: yugabyte_ycql_details.execute_request_sum,
+                    parse_request_count_diff: summed_counter_diff(yugabyte_ycql_details.parse_request_count, row.parse_request_count_counter, time_difference),
+                    parse_request_count_counter: yugabyte_ycql_details.parse_request_count,
+                    parse_request_sum_diff: summed_counter_diff(yugabyte_ycql_details.parse_request_sum, row.parse_request_sum_counter, time_difference),
+                    parse_request_sum_counter: yugabyte_ycql_details.parse_request_sum,
+                    cql_processors_alive: yugabyte_ycql_details.cql_processors_alive,
+                    cql_processors_created_diff: summed_counter_diff(yugabyte_ycql_details.cql_processors_created, row.cql_processors_created_counter, time_difference),
+                    cql_processors_created_counter: yugabyte_ycql_details.cql_processors_created,
+                    process_start_time: yugabyte_ycql_details.process_start_time,
+                    reset: false,
+                }
+            },
+            None => {
+                yugabyte_ycql_presentation.insert( yugabyte_ycql_details.hostname_port.to_string(), YBYcqlPresentation {
+                    timestamp: yugabyte_ycql_details.timestamp,
+                    select_count_diff: 0.0,
+                    select_count_counter: yugabyte_ycql_details.select_count,
+                    select_sum_diff: 0.0,
+                    select_sum_counter: yugabyte_ycql_details.select_sum,
+                    insert_count_diff: 0.0,
+                    insert_count_counter: yugabyte_ycql_details.insert_count,
+                    insert_sum_diff: 0.0,
+                    insert_sum_counter: yugabyte_ycql_details.insert_sum,
+                    execute_request_count_diff: 0.0,
+                    execute_request_count_counter: yugabyte_ycql_details.execute_request_count,
+                    execute_request_sum_diff: 0.0,
+                    execute_request_sum_counter: yugabyte_ycql_details.execute_request_sum,
+                    parse_request_count_diff: 0.0,
+                    parse_request_count_counter: yugabyte_ycql_details.parse_request_count,
+                    parse_request_sum_diff: 0.0,
+                    parse_request_sum_counter: yugabyte_ycql_details.parse_request_sum,
+                    cql_processors_alive: yugabyte_ycql_details.cql_processors_alive,
+                    cql_processors_created_diff: 0.0,
+                    cql_processors_created_counter: yugabyte_ycql_details.cql_processors_created,
+                    process_start_time: yugabyte_ycql_details.process_start_time,
+                    reset,
                 });
             },
         }
@@ -957,6 +3012,23 @@ pub fn diff_cpu_details(
     host_presentation: &mut BTreeMap<String, CpuPresentation>,
 ) {
     for host_details in values {
+        // idle and iowait time can go backwards a little on some kernels, so these are not used to detect a reset.
+        let reset = remove_reset_row(host_presentation, &host_details.hostname_port, |row| {
+            counters_reset(&[
+                (host_details.cpu_irq, row.irq_counter),
+                (host_details.cpu_softirq, row.softirq_counter),
+                (host_details.cpu_system, row.system_counter),
+                (host_details.cpu_user, row.user_counter),
+                (host_details.cpu_nice, row.nice_counter),
+                (host_details.cpu_steal, row.steal_counter),
+                (host_details.cpu_guest_nice, row.guest_nice_counter),
+                (host_details.cpu_guest_user, row.guest_user_counter),
+                (host_details.schedstat_running, row.schedstat_running_counter),
+                (host_details.schedstat_waiting, row.schedstat_waiting_counter),
+                (host_details.context_switches, row.context_switches_counter),
+                (host_details.interrupts, row.interrupts_counter),
+            ]) || start_time_changed(host_details.node_boot_time, row.node_boot_time)
+        });
         match host_presentation.get_mut(&host_details.hostname_port) {
             Some(row) => {
                 let time_difference = host_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.0;
@@ -996,6 +3068,10 @@ pub fn diff_cpu_details(
                     interrupts_diff: (host_details.interrupts - row.interrupts_counter)/time_difference,
                     interrupts_counter: host_details.interrupts,
 
+                    node_boot_time: host_details.node_boot_time,
+
+                    reset: false,
+
                 }
             },
             None => {
@@ -1034,6 +3110,200 @@ pub fn diff_cpu_details(
                     context_switches_counter: host_details.context_switches,
                     interrupts_diff: 0.0,
                     interrupts_counter: host_details.interrupts,
+                    node_boot_time: host_details.node_boot_time,
+                    reset,
+                });
+            },
+        }
+    }
+}
+
+pub fn cpu_cluster_total(
+    host_presentation: &BTreeMap<String, CpuPresentation>,
+    unreachable: &HashMap<String, String>,
+) -> Option<CpuPresentation> {
+    // the cluster total is the sum of the rates and the number of running and blocked processes of all hosts, and the
+    // average of their load. rows that are reset or unreachable are left out.
+    let rows: Vec<&CpuPresentation> = host_presentation
+        .iter()
+        .filter(|(key, row)| !row.reset && !unreachable.contains_key(key.split_whitespace().next().unwrap()))
+        .map(|(_, row)| row)
+        .collect();
+    // a total of a single host is the host itself, so it is not shown.
+    if rows.len() < 2 { return None };
+    let sum = |value: fn(&CpuPresentation) -> f64| rows.iter().map(|row| value(row)).sum::<f64>();
+    Some(CpuPresentation {
+        timestamp: rows.iter().map(|row| row.timestamp).max().unwrap(),
+        idle_diff: sum(|row| row.idle_diff),
+        idle_counter: 0.,
+        irq_diff: sum(|row| row.irq_diff),
+        irq_counter: 0.,
+        softirq_diff: sum(|row| row.softirq_diff),
+        softirq_counter: 0.,
+        system_diff: sum(|row| row.system_diff),
+        system_counter: 0.,
+        user_diff: sum(|row| row.user_diff),
+        user_counter: 0.,
+        iowait_diff: sum(|row| row.iowait_diff),
+        iowait_counter: 0.,
+        nice_diff: sum(|row| row.nice_diff),
+        nice_counter: 0.,
+        steal_diff: sum(|row| row.steal_diff),
+        steal_counter: 0.,
+        guest_nice_diff: sum(|row| row.guest_nice_diff),
+        guest_nice_counter: 0.,
+        guest_user_diff: sum(|row| row.guest_user_diff),
+        guest_user_counter: 0.,
+        schedstat_running_diff: sum(|row| row.schedstat_running_diff),
+        schedstat_running_counter: 0.,
+        schedstat_waiting_diff: sum(|row| row.schedstat_waiting_diff),
+        schedstat_waiting_counter: 0.,
+        load_1: sum(|row| row.load_1) / rows.len() as f64,
+        load_5: sum(|row| row.load_5) / rows.len() as f64,
+        load_15: sum(|row| row.load_15) / rows.len() as f64,
+        procs_running: sum(|row| row.procs_running),
+        procs_blocked: sum(|row| row.procs_blocked),
+        context_switches_diff: sum(|row| row.context_switches_diff),
+        context_switches_counter: 0.,
+        interrupts_diff: sum(|row| row.interrupts_diff),
+        interrupts_counter: 0.,
+        node_boot_time: 0.,
+        reset: false,
+    })
+}
+
+pub fn diff_cpu_core_details(
+    values: Vec<CpuCoreHost>,
+    cpu_core_presentation: &mut BTreeMap<String, CpuCorePresentation>,
+) {
+    for cpu_core_details in values {
+        for core in cpu_core_details.cpucoredetail {
+            // idle and iowait time can go backwards a little on some kernels, so these are not used to detect a reset.
+            let reset = remove_reset_row(cpu_core_presentation, &format!("{} {}", &cpu_core_details.hostname_port, core.cpu_name), |row| {
+                counters_reset(&[
+                    (core.cpu_irq, row.irq_counter),
+                    (core.cpu_softirq, row.softirq_counter),
+                    (core.cpu_system, row.system_counter),
+                    (core.cpu_user, row.user_counter),
+                    (core.cpu_nice, row.nice_counter),
+                    (core.cpu_steal, row.steal_counter),
+                ])
+            });
+            match cpu_core_presentation.get_mut(format!("{} {}", &cpu_core_details.hostname_port, core.cpu_name).as_str()) {
+                Some(row) => {
+                    let time_difference = cpu_core_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.0;
+                    *row = CpuCorePresentation {
+                        timestamp: cpu_core_details.timestamp,
+                        idle_diff: (core.cpu_idle - row.idle_counter)/time_difference,
+                        idle_counter: core.cpu_idle,
+                        irq_diff: (core.cpu_irq - row.irq_counter)/time_difference,
+                        irq_counter: core.cpu_irq,
+                        softirq_diff: (core.cpu_softirq - row.softirq_counter)/time_difference,
+                        softirq_counter: core.cpu_softirq,
+                        system_diff: (core.cpu_system - row.system_counter)/time_difference,
+                        system_counter: core.cpu_system,
+                        user_diff: (core.cpu_user - row.user_counter)/time_difference,
+                        user_counter: core.cpu_user,
+                        iowait_diff: (core.cpu_iowait - row.iowait_counter)/time_difference,
+                        iowait_counter: core.cpu_iowait,
+                        nice_diff: (core.cpu_nice - row.nice_counter)/time_difference,
+                        nice_counter: core.cpu_nice,
+                        steal_diff: (core.cpu_steal - row.steal_counter)/time_difference,
+                        steal_counter: core.cpu_steal,
+                        reset: false,
+                    }
+                },
+                None => {
+                    cpu_core_presentation.insert( format!("{} {}", cpu_core_details.hostname_port, core.cpu_name), CpuCorePresentation {
+                        timestamp: cpu_core_details.timestamp,
+                        idle_diff: 0.0,
+                        idle_counter: core.cpu_idle,
+                        irq_diff: 0.0,
+                        irq_counter: core.cpu_irq,
+                        softirq_diff: 0.0,
+                        softirq_counter: core.cpu_softirq,
+                        system_diff: 0.0,
+                        system_counter: core.cpu_system,
+                        user_diff: 0.0,
+                        user_counter: core.cpu_user,
+                        iowait_diff: 0.0,
+                        iowait_counter: core.cpu_iowait,
+                        nice_diff: 0.0,
+                        nice_counter: core.cpu_nice,
+                        steal_diff: 0.0,
+                        steal_counter: core.cpu_steal,
+                        reset,
+                    });
+                },
+            }
+        }
+    }
+}
+
+pub fn diff_memory_details(
+    values: Vec<MemoryDetails>,
+    memory_presentation: &mut BTreeMap<String, MemoryPresentation>,
+) {
+    for memory_details in values {
+        let reset = remove_reset_row(memory_presentation, &memory_details.hostname_port, |row| {
+            counters_reset(&[
+                (memory_details.pgpgin, row.pgpgin_counter),
+                (memory_details.pgpgout, row.pgpgout_counter),
+                (memory_details.pswpin, row.pswpin_counter),
+                (memory_details.pswpout, row.pswpout_counter),
+                (memory_details.pgmajfault, row.pgmajfault_counter),
+            ])
+        });
+        match memory_presentation.get_mut(&memory_details.hostname_port) {
+            Some(row) => {
+                let time_difference = memory_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.0;
+                *row = MemoryPresentation {
+                    timestamp: memory_details.timestamp,
+                    mem_total: memory_details.mem_total,
+                    mem_free: memory_details.mem_free,
+                    buffers: memory_details.buffers,
+                    cached: memory_details.cached,
+                    dirty: memory_details.dirty,
+                    writeback: memory_details.writeback,
+                    anon_pages: memory_details.anon_pages,
+                    slab: memory_details.slab,
+                    swap_free: memory_details.swap_free,
+                    pgpgin_diff: (memory_details.pgpgin - row.pgpgin_counter)/time_difference,
+                    pgpgin_counter: memory_details.pgpgin,
+                    pgpgout_diff: (memory_details.pgpgout - row.pgpgout_counter)/time_difference,
+                    pgpgout_counter: memory_details.pgpgout,
+                    pswpin_diff: (memory_details.pswpin - row.pswpin_counter)/time_difference,
+                    pswpin_counter: memory_details.pswpin,
+                    pswpout_diff: (memory_details.pswpout - row.pswpout_counter)/time_difference,
+                    pswpout_counter: memory_details.pswpout,
+                    pgmajfault_diff: (memory_details.pgmajfault - row.pgmajfault_counter)/time_difference,
+                    pgmajfault_counter: memory_details.pgmajfault,
+                    reset: false,
+                }
+            },
+            None => {
+                memory_presentation.insert( memory_details.hostname_port, MemoryPresentation {
+                    timestamp: memory_details.timestamp,
+                    mem_total: memory_details.mem_total,
+                    mem_free: memory_details.mem_free,
+                    buffers: memory_details.buffers,
+                    cached: memory_details.cached,
+                    dirty: memory_details.dirty,
+                    writeback: memory_details.writeback,
+                    anon_pages: memory_details.anon_pages,
+                    slab: memory_details.slab,
+                    swap_free: memory_details.swap_free,
+                    pgpgin_diff: 0.0,
+                    pgpgin_counter: memory_details.pgpgin,
+                    pgpgout_diff: 0.0,
+                    pgpgout_counter: memory_details.pgpgout,
+                    pswpin_diff: 0.0,
+                    pswpin_counter: memory_details.pswpin,
+                    pswpout_diff: 0.0,
+                    pswpout_counter: memory_details.pswpout,
+                    pgmajfault_diff: 0.0,
+                    pgmajfault_counter: memory_details.pgmajfault,
+                    reset,
                 });
             },
         }
@@ -1046,6 +3316,24 @@ pub fn diff_disk_details(
 ) {
     for disk_details in values {
         for disk in disk_details.diskdetail {
+            let reset = remove_reset_row(disk_presentation, &format!("{} {}", &disk_details.hostname_port, disk.disk_name), |row| {
+                counters_reset(&[
+                    (disk.reads_completed, row.reads_completed_counter),
+                    (disk.writes_completed, row.writes_completed_counter),
+                    (disk.discards_completed, row.discards_completed_counter),
+                    (disk.reads_merged, row.reads_merged_counter),
+                    (disk.writes_merged, row.writes_merged_counter),
+                    (disk.discards_merged, row.discards_merged_counter),
+                    (disk.reads_bytes, row.reads_bytes_counter),
+                    (disk.writes_bytes, row.writes_bytes_counter),
+                    (disk.discards_sectors, row.discards_sectors_counter),
+                    (disk.reads_time, row.reads_time_counter),
+                    (disk.writes_time, row.writes_time_counter),
+                    (disk.discards_time, row.discards_time_counter),
+                    (disk.total_time, row.disk_total_time_counter),
+                    (disk.queue, row.queue_counter),
+                ]) || start_time_changed(disk_details.node_boot_time, row.node_boot_time)
+            });
             match disk_presentation.get_mut(format!("{} {}", &disk_details.hostname_port.clone(), disk.disk_name).as_str()) {
                Some(row) => {
                     let time_difference = disk_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.0;
@@ -1058,7 +3346,7 @@ pub fn diff_disk_details(
                         discards_completed_diff: (disk.discards_completed - row.discards_completed_counter)/time_difference,
                         discards_completed_counter: disk.discards_completed,
                         reads_merged_diff: (disk.reads_merged - row.reads_merged_counter)/time_difference,
-                        reads_merged_counter: disk.discards_merged,
+                        reads_merged_counter: disk.reads_merged,
                         writes_merged_diff: (disk.writes_merged - row.writes_merged_counter)/time_difference,
                         writes_merged_counter: disk.writes_merged,
                         discards_merged_diff: (disk.discards_merged - row.discards_merged_counter)/time_difference,
@@ -1079,6 +3367,8 @@ pub fn diff_disk_details(
                         disk_total_time_counter: disk.total_time,
                         queue_diff: (disk.queue - row.queue_counter)/time_difference,
                         queue_counter: disk.queue,
+                        node_boot_time: disk_details.node_boot_time,
+                        reset: false,
                     }
                 },
                 None => {
@@ -1091,7 +3381,7 @@ pub fn diff_disk_details(
                         discards_completed_diff: 0.0,
                         discards_completed_counter: disk.discards_completed,
                         reads_merged_diff: 0.0,
-                        reads_merged_counter: disk.discards_merged,
+                        reads_merged_counter: disk.reads_merged,
                         writes_merged_diff: 0.0,
                         writes_merged_counter: disk.writes_merged,
                         discards_merged_diff: 0.0,
@@ -1112,6 +3402,282 @@ pub fn diff_disk_details(
                         disk_total_time_counter: disk.total_time,
                         queue_diff: 0.0,
                         queue_counter: disk.queue,
+                        node_boot_time: disk_details.node_boot_time,
+                        reset,
+                    });
+                },
+            }
+        }
+    }
+}
+
+pub fn disk_cluster_total(
+    disk_presentation: &BTreeMap<String, DiskPresentation>,
+    unreachable: &HashMap<String, String>,
+) -> Option<DiskPresentation> {
+    // the cluster total is the sum of the rates of all disks of all hosts. because the times are summed too, the latency
+    // calculated from the total is weighted by the number of IOs of every disk. rows that are reset or unreachable are left out.
+    let rows: Vec<(&String, &DiskPresentation)> = disk_presentation
+        .iter()
+        .filter(|(key, row)| !row.reset && !unreachable.contains_key(key.split_whitespace().next().unwrap()))
+        .collect();
+    // the total is of the cluster, so it is only shown for more than one host, not for a single host with more than one disk.
+    if rows.iter().map(|(key, _)| key.split_whitespace().next().unwrap()).unique().count() < 2 { return None };
+    let rows: Vec<&DiskPresentation> = rows.into_iter().map(|(_, row)| row).collect();
+    let sum = |value: fn(&DiskPresentation) -> f64| rows.iter().map(|row| value(row)).sum::<f64>();
+    Some(DiskPresentation {
+        timestamp: rows.iter().map(|row| row.timestamp).max().unwrap(),
+        reads_completed_diff: sum(|row| row.reads_completed_diff),
+        reads_completed_counter: 0.,
+        writes_completed_diff: sum(|row| row.writes_completed_diff),
+        writes_completed_counter: 0.,
+        discards_completed_diff: sum(|row| row.discards_completed_diff),
+        discards_completed_counter: 0.,
+        reads_merged_diff: sum(|row| row.reads_merged_diff),
+        reads_merged_counter: 0.,
+        writes_merged_diff: sum(|row| row.writes_merged_diff),
+        writes_merged_counter: 0.,
+        discards_merged_diff: sum(|row| row.discards_merged_diff),
+        discards_merged_counter: 0.,
+        reads_bytes_diff: sum(|row| row.reads_bytes_diff),
+        reads_bytes_counter: 0.,
+        writes_bytes_diff: sum(|row| row.writes_bytes_diff),
+        writes_bytes_counter: 0.,
+        discards_sectors_diff: sum(|row| row.discards_sectors_diff),
+        discards_sectors_counter: 0.,
+        reads_time_diff: sum(|row| row.reads_time_diff),
+        reads_time_counter: 0.,
+        writes_time_diff: sum(|row| row.writes_time_diff),
+        writes_time_counter: 0.,
+        discards_time_diff: sum(|row| row.discards_time_diff),
+        discards_time_counter: 0.,
+        disk_total_time_diff: sum(|row| row.disk_total_time_diff),
+        disk_total_time_counter: 0.,
+        queue_diff: sum(|row| row.queue_diff),
+        queue_counter: 0.,
+        node_boot_time: 0.,
+        reset: false,
+    })
+}
+
+pub fn diff_pressure_details(
+    values: Vec<PressureDetails>,
+    pressure_presentation: &mut BTreeMap<String, PressurePresentation>,
+) {
+    for pressure_details in values {
+        let reset = remove_reset_row(pressure_presentation, &pressure_details.hostname_port, |row| {
+            counters_reset(&[
+                (pressure_details.cpu_waiting, row.cpu_waiting_counter),
+                (pressure_details.io_waiting, row.io_waiting_counter),
+                (pressure_details.io_stalled, row.io_stalled_counter),
+                (pressure_details.memory_waiting, row.memory_waiting_counter),
+                (pressure_details.memory_stalled, row.memory_stalled_counter),
+            ])
+        });
+        match pressure_presentation.get_mut(&pressure_details.hostname_port) {
+            Some(row) => {
+                let time_difference = pressure_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.0;
+                *row = PressurePresentation {
+                    timestamp: pressure_details.timestamp,
+                    cpu_waiting_diff: (pressure_details.cpu_waiting - row.cpu_waiting_counter)/time_difference,
+                    cpu_waiting_counter: pressure_details.cpu_waiting,
+                    io_waiting_diff: (pressure_details.io_waiting - row.io_waiting_counter)/time_difference,
+                    io_waiting_counter: pressure_details.io_waiting,
+                    io_stalled_diff: (pressure_details.io_stalled - row.io_stalled_counter)/time_difference,
+                    io_stalled_counter: pressure_details.io_stalled,
+                    memory_waiting_diff: (pressure_details.memory_waiting - row.memory_waiting_counter)/time_difference,
+                    memory_waiting_counter: pressure_details.memory_waiting,
+                    memory_stalled_diff: (pressure_details.memory_stalled - row.memory_stalled_counter)/time_difference,
+                    memory_stalled_counter: pressure_details.memory_stalled,
+                    reset: false,
+                }
+            },
+            None => {
+                pressure_presentation.insert( pressure_details.hostname_port, PressurePresentation {
+                    timestamp: pressure_details.timestamp,
+                    cpu_waiting_diff: 0.0,
+                    cpu_waiting_counter: pressure_details.cpu_waiting,
+                    io_waiting_diff: 0.0,
+                    io_waiting_counter: pressure_details.io_waiting,
+                    io_stalled_diff: 0.0,
+                    io_stalled_counter: pressure_details.io_stalled,
+                    memory_waiting_diff: 0.0,
+                    memory_waiting_counter: pressure_details.memory_waiting,
+                    memory_stalled_diff: 0.0,
+                    memory_stalled_counter: pressure_details.memory_stalled,
+                    reset,
+                });
+            },
+        }
+    }
+}
+
+pub fn diff_tcp_details(
+    values: Vec<TcpDetails>,
+    tcp_presentation: &mut BTreeMap<String, TcpPresentation>,
+) {
+    for tcp_details in values {
+        let reset = remove_reset_row(tcp_presentation, &tcp_details.hostname_port, |row| {
+            counters_reset(&[
+                (tcp_details.retrans_segs, row.retrans_segs_counter),
+                (tcp_details.in_segs, row.in_segs_counter),
+                (tcp_details.out_segs, row.out_segs_counter),
+                (tcp_details.listen_overflows, row.listen_overflows_counter),
+                (tcp_details.listen_drops, row.listen_drops_counter),
+                (tcp_details.tcp_timeouts, row.tcp_timeouts_counter),
+            ])
+        });
+        match tcp_presentation.get_mut(&tcp_details.hostname_port) {
+            Some(row) => {
+                let time_difference = tcp_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.0;
+                *row = TcpPresentation {
+                    timestamp: tcp_details.timestamp,
+                    retrans_segs_diff: (tcp_details.retrans_segs - row.retrans_segs_counter)/time_difference,
+                    retrans_segs_counter: tcp_details.retrans_segs,
+                    in_segs_diff: (tcp_details.in_segs - row.in_segs_counter)/time_difference,
+                    in_segs_counter: tcp_details.in_segs,
+                    out_segs_diff: (tcp_details.out_segs - row.out_segs_counter)/time_difference,
+                    out_segs_counter: tcp_details.out_segs,
+                    listen_overflows_diff: (tcp_details.listen_overflows - row.listen_overflows_counter)/time_difference,
+                    listen_overflows_counter: tcp_details.listen_overflows,
+                    listen_drops_diff: (tcp_details.listen_drops - row.listen_drops_counter)/time_difference,
+                    listen_drops_counter: tcp_details.listen_drops,
+                    tcp_timeouts_diff: (tcp_details.tcp_timeouts - row.tcp_timeouts_counter)/time_difference,
+                    tcp_timeouts_counter: tcp_details.tcp_timeouts,
+                    tcp_inuse: tcp_details.tcp_inuse,
+                    tcp_tw: tcp_details.tcp_tw,
+                    tcp_alloc: tcp_details.tcp_alloc,
+                    reset: false,
+                }
+            },
+            None => {
+                tcp_presentation.insert( tcp_details.hostname_port, TcpPresentation {
+                    timestamp: tcp_details.timestamp,
+                    retrans_segs_diff: 0.0,
+                    retrans_segs_counter: tcp_details.retrans_segs,
+                    in_segs_diff: 0.0,
+                    in_segs_counter: tcp_details.in_segs,
+                    out_segs_diff: 0.0,
+                    out_segs_counter: tcp_details.out_segs,
+                    listen_overflows_diff: 0.0,
+                    listen_overflows_counter: tcp_details.listen_overflows,
+                    listen_drops_diff: 0.0,
+                    listen_drops_counter: tcp_details.listen_drops,
+                    tcp_timeouts_diff: 0.0,
+                    tcp_timeouts_counter: tcp_details.tcp_timeouts,
+                    tcp_inuse: tcp_details.tcp_inuse,
+                    tcp_tw: tcp_details.tcp_tw,
+                    tcp_alloc: tcp_details.tcp_alloc,
+                    reset,
+                });
+            },
+        }
+    }
+}
+
+pub fn diff_network_details(
+    values: Vec<NetworkHost>,
+    network_presentation: &mut BTreeMap<String, NetworkPresentation>,
+) {
+    for network_details in values {
+        for interface in network_details.networkdetail {
+            let reset = remove_reset_row(network_presentation, &format!("{} {}", &network_details.hostname_port, interface.interface_name), |row| {
+                counters_reset(&[
+                    (interface.receive_bytes, row.receive_bytes_counter),
+                    (interface.transmit_bytes, row.transmit_bytes_counter),
+                    (interface.receive_packets, row.receive_packets_counter),
+                    (interface.transmit_packets, row.transmit_packets_counter),
+                    (interface.receive_errs, row.receive_errs_counter),
+                    (interface.transmit_errs, row.transmit_errs_counter),
+                    (interface.receive_drop, row.receive_drop_counter),
+                    (interface.transmit_drop, row.transmit_drop_counter),
+                ])
+            });
+            match network_presentation.get_mut(format!("{} {}", &network_details.hostname_port, interface.interface_name).as_str()) {
+                Some(row) => {
+                    let time_difference = network_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.0;
+                    *row = NetworkPresentation {
+                        timestamp: network_details.timestamp,
+                        receive_bytes_diff: (interface.receive_bytes - row.receive_bytes_counter)/time_difference,
+                        receive_bytes_counter: interface.receive_bytes,
+                        transmit_bytes_diff: (interface.transmit_bytes - row.transmit_bytes_counter)/time_difference,
+                        transmit_bytes_counter: interface.transmit_bytes,
+                        receive_packets_diff: (interface.receive_packets - row.receive_packets_counter)/time_difference,
+                        receive_packets_counter: interface.receive_packets,
+                        transmit_packets_diff: (interface.transmit_packets - row.transmit_packets_counter)/time_difference,
+                        transmit_packets_counter: interface.transmit_packets,
+                        receive_errs_diff: (interface.receive_errs - row.receive_errs_counter)/time_difference,
+                        receive_errs_counter: interface.receive_errs,
+                        transmit_errs_diff: (interface.transmit_errs - row.transmit_errs_counter)/time_difference,
+                        transmit_errs_counter: interface.transmit_errs,
+                        receive_drop_diff: (interface.receive_drop - row.receive_drop_counter)/time_difference,
+                        receive_drop_counter: interface.receive_drop,
+                        transmit_drop_diff: (interface.transmit_drop - row.transmit_drop_counter)/time_difference,
+                        transmit_drop_counter: interface.transmit_drop,
+                        speed_bytes: interface.speed_bytes,
+                        reset: false,
+                    }
+                },
+                None => {
+                    network_presentation.insert( format!("{} {}", network_details.hostname_port, interface.interface_name), NetworkPresentation {
+                        timestamp: network_details.timestamp,
+                        receive_bytes_diff: 0.0,
+                        receive_bytes_counter: interface.receive_bytes,
+                        transmit_bytes_diff: 0.0,
+                        transmit_bytes_counter: interface.transmit_bytes,
+                        receive_packets_diff: 0.0,
+                        receive_packets_counter: interface.receive_packets,
+                        transmit_packets_diff: 0.0,
+                        transmit_packets_counter: interface.transmit_packets,
+                        receive_errs_diff: 0.0,
+                        receive_errs_counter: interface.receive_errs,
+                        transmit_errs_diff: 0.0,
+                        transmit_errs_counter: interface.transmit_errs,
+                        receive_drop_diff: 0.0,
+                        receive_drop_counter: interface.receive_drop,
+                        transmit_drop_diff: 0.0,
+                        transmit_drop_counter: interface.transmit_drop,
+                        speed_bytes: interface.speed_bytes,
+                        reset,
+                    });
+                },
+            }
+        }
+    }
+}
+
+pub fn diff_filesystem_details(
+    values: Vec<FilesystemHost>,
+    filesystem_presentation: &mut BTreeMap<String, FilesystemPresentation>,
+) {
+    for filesystem_details in values {
+        for filesystem in filesystem_details.filesystemdetail {
+            match filesystem_presentation.get_mut(format!("{} {}", &filesystem_details.hostname_port, filesystem.mountpoint).as_str()) {
+                Some(row) => {
+                    let time_difference = filesystem_details.timestamp.signed_duration_since(row.timestamp).num_milliseconds() as f64 / 1000.0;
+                    *row = FilesystemPresentation {
+                        timestamp: filesystem_details.timestamp,
+                        device: filesystem.device,
+                        fstype: filesystem.fstype,
+                        size_bytes: filesystem.size_bytes,
+                        avail_bytes_diff: (filesystem.avail_bytes - row.avail_bytes_counter)/time_difference,
+                        avail_bytes_counter: filesystem.avail_bytes,
+                        files: filesystem.files,
+                        files_free: filesystem.files_free,
+                        reset: false,
+                    }
+                },
+                None => {
+                    filesystem_presentation.insert( format!("{} {}", filesystem_details.hostname_port, filesystem.mountpoint), FilesystemPresentation {
+                        timestamp: filesystem_details.timestamp,
+                        device: filesystem.device,
+                        fstype: filesystem.fstype,
+                        size_bytes: filesystem.size_bytes,
+                        avail_bytes_diff: 0.0,
+                        avail_bytes_counter: filesystem.avail_bytes,
+                        files: filesystem.files,
+                        files_free: filesystem.files_free,
+                        reset: false,
                     });
                 },
             }
@@ -1147,4 +3713,178 @@ log_bytes_logged_counter: disk_details.yugabyte_io.log_bytes_logged,
 },
 }
 
- */
\ No newline at end of file
+ */
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    // a prometheus server that answers every request with the given json, and keeps the request lines.
+    fn mock_prometheus(
+        json: &'static str,
+    ) -> (Target, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let requests_thread = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut header = String::new();
+                while reader.read_line(&mut header).unwrap() > 2 {
+                    header.clear();
+                }
+                requests_thread.lock().unwrap().push(request_line.trim_end().to_string());
+                write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", json.len(), json).unwrap();
+            }
+        });
+        let connection = Connection { connect_timeout: 1, read_timeout: 5, ..Default::default() };
+        (parse_prometheus(&format!("127.0.0.1:{}", port), &connection).unwrap(), requests)
+    }
+
+    fn timestamp(seconds: f64) -> DateTime<Utc> {
+        DateTime::<Utc>::from(UNIX_EPOCH + Duration::from_secs_f64(seconds))
+    }
+
+    fn value<'a>(values: &'a [NodeExporterValues], name: &str) -> &'a NodeExporterValues {
+        values.iter().find(|value| value.node_exporter_name == name).unwrap()
+    }
+
+    #[test]
+    fn latency_percentile_interpolates_in_the_bucket_deltas() {
+        let previous = [(100., 0.), (200., 0.), (500., 0.), (f64::INFINITY, 0.)];
+        let buckets = [(100., 10.), (200., 30.), (500., 45.), (f64::INFINITY, 50.)];
+        // the rank of p50 is 25, which is 15 of the 20 samples between 100 and 200.
+        assert_eq!(latency_percentile(&buckets, &previous, &[], 0.50), 175.);
+        // the rank of p95 is 47.5, which is in the +Inf bucket, so the lower bound of that bucket is taken.
+        assert_eq!(latency_percentile(&buckets, &previous, &[], 0.95), 500.);
+        // without samples between the two scrapes there is no percentile.
+        assert_eq!(latency_percentile(&buckets, &buckets, &[], 0.50), 0.);
+        // a tablet that moved away lowers the summed buckets, which makes the deltas not cumulative.
+        let previous = [(100., 0.), (200., 30.), (500., 30.), (f64::INFINITY, 30.)];
+        let buckets = [(100., 10.), (200., 35.), (500., 40.), (f64::INFINITY, 40.)];
+        assert_eq!(latency_percentile(&buckets, &previous, &[], 0.50), 0.);
+    }
+
+    #[test]
+    fn latency_percentile_uses_the_quantiles_without_buckets() {
+        let quantiles = [(0.5, 800.), (0.99, 3000.)];
+        assert_eq!(latency_percentile(&[], &[], &quantiles, 0.50), 800.);
+        assert_eq!(latency_percentile(&[], &[], &quantiles, 0.99), 3000.);
+        assert_eq!(latency_percentile(&[], &[], &quantiles, 0.95), 0.);
+    }
+
+    #[test]
+    fn recording_is_read_back_as_recorded() {
+        let file = std::env::temp_dir().join(format!("nodetop-test-{}.rec.gz", std::process::id()));
+        let labels = ["_ext4 /dev/sda1 /mnt/a\tb\nc \\d", "_t1", ""];
+        let node_values: HashMap<String, Vec<NodeExporterValues>> = HashMap::from([("10.0.0.1:9300:metrics".to_string(), labels.iter().enumerate().map(|(index, labels)| NodeExporterValues {
+            node_exporter_name: "node_filesystem_size_bytes".to_string(),
+            node_exporter_type: "gauge".to_string(),
+            node_exporter_labels: labels.to_string(),
+            node_exporter_category: "all".to_string(),
+            node_exporter_timestamp: timestamp(1000.5),
+            node_exporter_value: index as f64 + 0.25,
+        }).collect())]);
+        let unreachable: HashMap<String, String> = HashMap::from([("10.0.0.2:9300:metrics".to_string(), "error\twith\ntabs".to_string())]);
+        {
+            let mut record_file = fs::File::create(&file).unwrap();
+            record_scrapes(&mut record_file, &node_values, &unreachable).unwrap();
+            record_scrapes(&mut record_file, &node_values, &HashMap::new()).unwrap();
+        }
+        let intervals: Vec<RecordedInterval> = read_recording(file.to_str().unwrap()).unwrap().collect::<Result<_, _>>().unwrap();
+        fs::remove_file(&file).unwrap();
+        assert_eq!(intervals.len(), 2);
+        let values = &intervals[0].0["10.0.0.1:9300:metrics"];
+        assert_eq!(values.iter().map(|value| value.node_exporter_labels.as_str()).collect::<Vec<_>>(), labels);
+        assert_eq!(values.iter().map(|value| value.node_exporter_value).collect::<Vec<_>>(), [0.25, 1.25, 2.25]);
+        assert_eq!(values[0].node_exporter_timestamp, timestamp(1000.5));
+        assert_eq!(intervals[0].1["10.0.0.2:9300:metrics"], "error with tabs");
+        assert_eq!(intervals[1].0["10.0.0.1:9300:metrics"].len(), 3);
+        assert!(intervals[1].1.is_empty());
+    }
+
+    #[test]
+    fn secrets_are_not_shown_in_debug_output() {
+        let connection = Connection { user: Some("yugabyte".to_string()), password: Some("s3cret".to_string()), bearer_token: Some("t0ken".to_string()), ..Default::default() };
+        let target = parse_prometheus("127.0.0.1:9090", &connection).unwrap();
+        let debug = format!("{:?}", target);
+        assert!(debug.contains("yugabyte") && debug.contains("<redacted>"), "{}", debug);
+        assert!(!debug.contains("s3cret") && !debug.contains("t0ken"), "{}", debug);
+    }
+
+    #[test]
+    fn prometheus_series_are_grouped_by_instance_and_job() {
+        let node = serde_json::json!({"__name__": "node_load1", "instance": "10.0.0.1:9300", "job": "node"});
+        let tserver = serde_json::json!({"__name__": "log_bytes_logged", "instance": "10.0.0.1:9000", "job": "tserver", "metric_id": "t1"});
+        let other_node = serde_json::json!({"__name__": "node_load5", "instance": "10.0.0.2:9300", "job": "node"});
+        let same_node = serde_json::json!({"__name__": "node_load5", "instance": "10.0.0.1:9300", "job": "node"});
+        let series = vec![
+            (&node, timestamp(1000.), "1.5"),
+            (&tserver, timestamp(1001.), "100"),
+            (&other_node, timestamp(1002.), "2.5"),
+            (&same_node, timestamp(1000.), "1.2"),
+        ];
+        let map = prometheus_series_into_map(&series);
+        assert_eq!(map.keys().sorted().collect::<Vec<_>>(), vec!["10.0.0.1:9000:tserver", "10.0.0.1:9300:node", "10.0.0.2:9300:node"]);
+        assert_eq!(map["10.0.0.1:9300:node"].len(), 2);
+        assert_eq!(value(&map["10.0.0.1:9300:node"], "node_load1").node_exporter_value, 1.5);
+        assert_eq!(value(&map["10.0.0.1:9300:node"], "node_load5").node_exporter_value, 1.2);
+        // the instance and job labels are not kept as labels of the sample.
+        assert_eq!(value(&map["10.0.0.1:9000:tserver"], "log_bytes_logged").node_exporter_labels, "_t1");
+    }
+
+    #[test]
+    fn prometheus_labels_are_escaped_as_in_prometheus_format() {
+        let metric = serde_json::json!({"__name__": "node_filesystem_size_bytes", "instance": "10.0.0.1:9300", "job": "node", "device": "/dev/sda1", "fstype": "ext4", "mountpoint": "/mnt/a \"b\" c\\d"});
+        let map = prometheus_series_into_map(&[(&metric, timestamp(1000.), "100")]);
+        // the labels are the same as when the sample is read from the endpoint.
+        let from_endpoint = parse_node_exporter("node_filesystem_size_bytes{device=\"/dev/sda1\",fstype=\"ext4\",mountpoint=\"/mnt/a \\\"b\\\" c\\\\d\"} 100\n".to_string(), timestamp(1000.));
+        assert_eq!(map["10.0.0.1:9300:node"][0].node_exporter_labels, from_endpoint[0].node_exporter_labels);
+        assert_eq!(map["10.0.0.1:9300:node"][0].node_exporter_value, 100.);
+    }
+
+    #[test]
+    fn prometheus_query_uses_the_last_sample_and_its_timestamp() {
+        let (prometheus, requests) = mock_prometheus(r#"{"status":"success","data":{"resultType":"matrix","result":[
+            {"metric":{"__name__":"node_procs_running","instance":"10.0.0.1:9300","job":"node"},"values":[[1000.5,"3"],[1015.5,"4"]]},
+            {"metric":{"__name__":"node_procs_running","instance":"10.0.0.2:9300","job":"node"},"values":[[1010.25,"7"]]}
+        ]}}"#);
+        let map = read_prometheus_into_map(&prometheus, "{job=\"node\"}", 60).unwrap();
+        assert_eq!(map.len(), 2);
+        let first = value(&map["10.0.0.1:9300:node"], "node_procs_running");
+        assert_eq!(first.node_exporter_value, 4.);
+        assert_eq!(first.node_exporter_timestamp, timestamp(1015.5));
+        assert_eq!(value(&map["10.0.0.2:9300:node"], "node_procs_running").node_exporter_timestamp, timestamp(1010.25));
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.starts_with("GET /api/v1/query?query="), "{}", request);
+        assert!(request.contains("%5B60s%5D"), "{}", request);
+    }
+
+    #[test]
+    fn prometheus_range_is_an_interval_per_step() {
+        let (prometheus, requests) = mock_prometheus(r#"{"status":"success","data":{"resultType":"matrix","result":[
+            {"metric":{"__name__":"node_intr_total","instance":"10.0.0.1:9300","job":"node"},"values":[[1000,"10"],[1010,"20"],[1020,"30"]]},
+            {"metric":{"__name__":"node_intr_total","instance":"10.0.0.2:9300","job":"node"},"values":[[1010,"200"],[1020,"300"]]}
+        ]}}"#);
+        let window = read_prometheus_range(&prometheus, "{job=\"node\"}", timestamp(1000.), timestamp(1020.), 10).unwrap();
+        assert_eq!(window.len(), 3);
+        assert_eq!(window[0].len(), 1);
+        assert_eq!(value(&window[0]["10.0.0.1:9300:node"], "node_intr_total").node_exporter_value, 10.);
+        for (step, time) in window.iter().zip([1000., 1010., 1020.]) {
+            for values in step.values() {
+                assert_eq!(values[0].node_exporter_timestamp, timestamp(time));
+            }
+        }
+        assert_eq!(value(&window[2]["10.0.0.2:9300:node"], "node_intr_total").node_exporter_value, 300.);
+        let request = requests.lock().unwrap()[0].clone();
+        assert!(request.starts_with("GET /api/v1/query_range?"), "{}", request);
+        assert!(request.contains("start=1000") && request.contains("end=1020") && request.contains("step=10s"), "{}", request);
+    }
+}
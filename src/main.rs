@@ -1,16 +1,24 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use structopt::StructOpt;
 use std::{thread, time};
 //use std::intrinsics::offset;
 use std::process;
+use std::{fs, io};
+use std::io::Write;
 use chrono::{DateTime, Utc};
-use ctrlc;
 use std::sync::{Arc, Mutex};
 use plotters::prelude::*;
 use itertools::Itertools;
+use serde::Serialize;
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Alignment, Constraint};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Cell, Row, Table};
 use plotters::chart::SeriesLabelPosition::UpperLeft;
 
-use nodetop::{read_node_exporter_into_map, cpu_details, diff_cpu_details, disk_details, CpuPresentation, DiskPresentation, diff_disk_details, YBIOPresentation, yugabyte_details, diff_yugabyte_details};
+use nodetop::{Connection, Target, YUGABYTE_IO_METRICS, YUGABYTE_RPC_METRICS, YUGABYTE_YSQL_METRICS, YUGABYTE_YCQL_METRICS, NODE_EXPORTER_CPU_METRICS, NODE_EXPORTER_MEMORY_METRICS, NODE_EXPORTER_PRESSURE_METRICS, NODE_EXPORTER_DISK_METRICS, NODE_EXPORTER_FILESYSTEM_METRICS, NODE_EXPORTER_NETWORK_METRICS, NODE_EXPORTER_TCP_METRICS, parse_prometheus, RecordedInterval, record_scrapes, read_recording, read_prometheus_into_map, read_prometheus_range, parse_targets, read_targets_file, parse_master, parse_node_exporter_template, read_secret_file, discover_cluster, discover_endpoints, target_hostname_port, read_node_exporter_into_map, cpu_details, diff_cpu_details, cpu_cluster_total, disk_details, CpuPresentation, DiskPresentation, diff_disk_details, disk_cluster_total, YBIOPresentation, yugabyte_details, diff_yugabyte_details, yugabyte_cluster_total, MemoryPresentation, memory_details, diff_memory_details, NetworkPresentation, network_details, diff_network_details, TcpPresentation, tcp_details, diff_tcp_details, FilesystemPresentation, filesystem_details, diff_filesystem_details, PressurePresentation, pressure_details, diff_pressure_details, CpuCorePresentation, cpu_core_details, diff_cpu_core_details, YBRpcPresentation, yugabyte_rpc_details, diff_yugabyte_rpc_details, YBYsqlPresentation, yugabyte_ysql_details, diff_yugabyte_ysql_details, YBYcqlPresentation, yugabyte_ycql_details, diff_yugabyte_ycql_details};
 
 #[derive(Debug)]
 struct CpuGraph {
@@ -26,6 +34,37 @@ struct CpuGraph {
     idle: f64,
     scheduler_runtime: f64,
     scheduler_wait: f64,
+    reset: bool,
+}
+
+#[derive(Debug)]
+struct MemoryGraph {
+    hostname: String,
+    timestamp: DateTime<Utc>,
+    anon_pages: f64,
+    slab: f64,
+    buffers: f64,
+    cached: f64,
+    free: f64,
+    total: f64,
+    pgpgin: f64,
+    pgpgout: f64,
+    pswpin: f64,
+    pswpout: f64,
+    pgmajfault: f64,
+    reset: bool,
+}
+
+#[derive(Debug)]
+struct PressureGraph {
+    hostname: String,
+    timestamp: DateTime<Utc>,
+    cpu_waiting: f64,
+    io_waiting: f64,
+    memory_waiting: f64,
+    io_stalled: f64,
+    memory_stalled: f64,
+    reset: bool,
 }
 
 #[derive(Debug)]
@@ -42,6 +81,19 @@ struct DiskGraph {
     writes_bytes: f64,
     writes_time: f64,
     queue: f64,
+    reset: bool,
+}
+
+#[derive(Debug)]
+struct NetworkGraph {
+    hostname: String,
+    timestamp: DateTime<Utc>,
+    interface: String,
+    receive_bytes: f64,
+    transmit_bytes: f64,
+    receive_packets: f64,
+    transmit_packets: f64,
+    reset: bool,
 }
 
 #[derive(Debug)]
@@ -64,6 +116,19 @@ struct YBIOGraph {
     rocksdb_write_raw_block_micros_sum: f64,
     rocksdb_sst_read_micros_count: f64,
     rocksdb_sst_read_micros_sum: f64,
+    log_append_latency_p50: f64,
+    log_append_latency_p95: f64,
+    log_append_latency_p99: f64,
+    log_sync_latency_p50: f64,
+    log_sync_latency_p95: f64,
+    log_sync_latency_p99: f64,
+    rocksdb_sst_read_micros_p50: f64,
+    rocksdb_sst_read_micros_p95: f64,
+    rocksdb_sst_read_micros_p99: f64,
+    rocksdb_write_raw_block_micros_p50: f64,
+    rocksdb_write_raw_block_micros_p95: f64,
+    rocksdb_write_raw_block_micros_p99: f64,
+    reset: bool,
 }
 
 const DEFAULT_HOSTNAMES: &str = "192.168.66.80";
@@ -72,24 +137,150 @@ const INTERVAL: &str = "5";
 
 #[derive(Debug, StructOpt)]
 struct Opts {
-    /// hostnames (comma separated)
+    /// hostnames, or targets as [scheme://][role@]hostname[:port][/endpoint] (comma separated)
     #[structopt(short, long, default_value = DEFAULT_HOSTNAMES)]
     hosts: String,
+    /// file with a target per line, optionally followed by settings for the target, instead of the hostnames
+    #[structopt(long)]
+    targets_file: Option<String>,
+    /// master address ([scheme://]hostname[:port]) to discover the cluster from, instead of the hostnames
+    #[structopt(long)]
+    master: Option<String>,
+    /// seconds between discovering the cluster from the master
+    #[structopt(long, default_value = "60")]
+    master_refresh: u64,
+    /// scheme of the node_exporters discovered from the master
+    #[structopt(long, default_value = "http", possible_values = &["http", "https"])]
+    node_exporter_scheme: String,
+    /// settings of the node_exporters discovered from the master, as in the targets file (space separated)
+    #[structopt(long, default_value = "")]
+    node_exporter_settings: String,
+    /// prometheus server ([scheme://]hostname[:port][/path]) to read the statistics from, instead of the hostnames
+    #[structopt(long)]
+    prometheus: Option<String>,
+    /// prometheus selector for the series to read (default the series of the metrics that the enabled modes use)
+    #[structopt(long)]
+    prometheus_selector: Option<String>,
+    /// start of a past time window to read from prometheus (RFC 3339, such as 2022-06-01T12:00:00Z)
+    #[structopt(long)]
+    prometheus_start: Option<String>,
+    /// end of a past time window to read from prometheus (RFC 3339, default now)
+    #[structopt(long)]
+    prometheus_end: Option<String>,
+    /// append the raw scrapes to a file, so they can be replayed later
+    #[structopt(long)]
+    record: Option<String>,
+    /// read the scrapes from a file written with --record instead of from the endpoints
+    #[structopt(long)]
+    replay: Option<String>,
     /// port numbers (comma separated)
     #[structopt(short, long, default_value = DEFAULT_PORTS)]
     ports: String,
     /// cpu statistics
     #[structopt(short, long)]
     cpu: bool,
+    /// cpu statistics per cpu
+    #[structopt(long)]
+    cpu_detail: bool,
+    /// only show the number of busiest cpus per host for cpu statistics per cpu
+    #[structopt(long)]
+    cpu_top: Option<usize>,
+    /// memory statistics
+    #[structopt(short, long)]
+    memory: bool,
+    /// pressure stall statistics
+    #[structopt(short = "P", long)]
+    pressure: bool,
     /// disk statistics
     #[structopt(short, long)]
     disk: bool,
+    /// filesystem statistics
+    #[structopt(short, long)]
+    filesystem: bool,
+    /// filesystem mountpoints to show (comma separated, default all)
+    #[structopt(long, default_value = "")]
+    mountpoints: String,
+    /// network statistics
+    #[structopt(short, long)]
+    network: bool,
+    /// tcp statistics
+    #[structopt(short, long)]
+    tcp: bool,
     /// yugabyte statistics
     #[structopt(short, long)]
     yb: bool,
+    /// yugabyte rpc statistics
+    #[structopt(short, long)]
+    rpc: bool,
+    /// yugabyte ysql statistics
+    #[structopt(short = "s", long)]
+    ysql: bool,
+    /// yugabyte ycql statistics
+    #[structopt(short = "q", long)]
+    ycql: bool,
+    /// yugabyte latency percentiles
+    #[structopt(long)]
+    percentiles: bool,
+    /// number of endpoints read at the same time (default all, at most 64)
+    #[structopt(long)]
+    parallel: Option<usize>,
+    /// timeout in seconds for connecting to an endpoint
+    #[structopt(long, default_value = "1")]
+    connect_timeout: u64,
+    /// timeout in seconds for reading an endpoint
+    #[structopt(long, default_value = "5")]
+    read_timeout: u64,
+    /// CA bundle (PEM) to verify https endpoints with
+    #[structopt(long)]
+    ca_cert: Option<String>,
+    /// client certificate (PEM) for https endpoints
+    #[structopt(long)]
+    client_cert: Option<String>,
+    /// client key (PEM, PKCS#8) for https endpoints
+    #[structopt(long)]
+    client_key: Option<String>,
+    /// do not verify the certificates of https endpoints
+    #[structopt(long)]
+    insecure: bool,
+    /// user for basic authentication
+    #[structopt(long)]
+    user: Option<String>,
+    /// password for basic authentication
+    #[structopt(long, env = "NODETOP_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
+    /// file with the password for basic authentication
+    #[structopt(long)]
+    password_file: Option<String>,
+    /// token for bearer authentication
+    #[structopt(long, env = "NODETOP_BEARER_TOKEN", hide_env_values = true)]
+    bearer_token: Option<String>,
+    /// file with the token for bearer authentication
+    #[structopt(long)]
+    bearer_token_file: Option<String>,
+    /// only request the metrics the enabled yugabyte modes use from yugabyte
+    #[structopt(long)]
+    metric_filter: bool,
+    /// show the time and size of reading every endpoint
+    #[structopt(long)]
+    scrape_statistics: bool,
     /// interval in seconds
     #[structopt(short, long, default_value = INTERVAL)]
     interval: u64,
+    /// output format (text, csv or jsonl, csv is available for the cpu, disk and yugabyte statistics)
+    #[structopt(long, default_value = "text", possible_values = &["text", "csv", "jsonl"])]
+    output: String,
+    /// directory to write the csv output to as a file per mode (cpu.csv, disk.csv, yugabyte.csv) instead of to stdout
+    #[structopt(long)]
+    output_directory: Option<String>,
+    /// file to append the jsonl output to instead of stdout
+    #[structopt(long)]
+    output_file: Option<String>,
+    /// full-screen view of the cpu, disk and yugabyte statistics, switched with the c, d and y keys
+    #[structopt(long)]
+    tui: bool,
+    /// number of samples in the sparklines of the full-screen view
+    #[structopt(long, default_value = "30")]
+    tui_samples: usize,
     /// headers every number of lines
     #[structopt(short, long, default_value = "60")]
     lines_for_header: u64,
@@ -101,70 +292,336 @@ struct Opts {
     graph_addition: Option<String>,
 }
 
+// the intervals of a past time window or of a recording, which are shown one after the other without waiting.
+type Window = std::iter::Peekable<Box<dyn Iterator<Item = Result<RecordedInterval, String>>>>;
+
 fn main() {
     let options = Opts::from_args();
     let hosts_string = &options.hosts as &str;
-    let hosts = &hosts_string.split(",").collect();
+    let hosts: Vec<&str> = hosts_string.split(',').collect();
     let ports_string = &options.ports as &str;
-    let ports = &ports_string.split(",").collect();
-    let cpu = options.cpu as bool;
-    let disk = options.disk as bool;
-    let yb = options.yb as bool;
-    let interval = options.interval as u64;
-    let lines_for_header = options.lines_for_header as u64;
-    let graph = options.graph as bool;
-
-    let graph_name_addition = match options.graph_addition {
+    let ports: Vec<&str> = ports_string.split(',').collect();
+    // a password or token can also be read from a file or the environment, so that it is not visible in the process list.
+    let read_secret = |file: &Option<String>, secret: &Option<String>| match file {
+        Some(file) => Some(read_secret_file(file).unwrap_or_else(|error| {
+            eprintln!("{}", error);
+            process::exit(1);
+        })),
+        None => secret.clone(),
+    };
+    // the connection settings apply to all targets, but can be set per target in the targets file.
+    let connection = Connection {
+        connect_timeout: options.connect_timeout,
+        read_timeout: options.read_timeout,
+        ca_cert: options.ca_cert.clone(),
+        client_cert: options.client_cert.clone(),
+        client_key: options.client_key.clone(),
+        insecure: options.insecure,
+        user: options.user.clone(),
+        password: read_secret(&options.password_file, &options.password),
+        bearer_token: read_secret(&options.bearer_token_file, &options.bearer_token),
+    };
+    let master = options.master.as_ref().map(|master| parse_master(master, &connection)).transpose().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(1);
+    });
+    let node_exporter = parse_node_exporter_template(&options.node_exporter_scheme, &options.node_exporter_settings, &connection).unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(1);
+    });
+    let prometheus = options.prometheus.as_ref().map(|prometheus| parse_prometheus(prometheus, &connection)).transpose().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(1);
+    });
+    let mut targets = match (&master, &options.targets_file) {
+        _ if prometheus.is_some() || options.replay.is_some() => Ok(Vec::new()),
+        (Some(master), _) => discover_cluster(std::slice::from_ref(master), &node_exporter),
+        (None, Some(targets_file)) => read_targets_file(targets_file, &ports, &connection),
+        (None, None) => parse_targets(&hosts, &ports, &connection),
+    }.unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(1);
+    });
+    let mut discovery_time = time::Instant::now();
+    let cpu = options.cpu;
+    let cpu_detail = options.cpu_detail || options.cpu_top.is_some();
+    let cpu_top = options.cpu_top;
+    let memory = options.memory;
+    let pressure = options.pressure;
+    let disk = options.disk;
+    let filesystem = options.filesystem;
+    let mountpoints_string = &options.mountpoints as &str;
+    let mountpoints: Vec<&str> = mountpoints_string.split(',').filter(|x| !x.is_empty()).collect();
+    let network = options.network;
+    let tcp = options.tcp;
+    let yb = options.yb;
+    let rpc = options.rpc;
+    let ysql = options.ysql;
+    let ycql = options.ycql;
+    let percentiles = options.percentiles;
+    let tui = options.tui;
+    // the pool that reads the endpoints is created once, and only created again when the number of targets changes.
+    let mut parallel = options.parallel.unwrap_or_else(|| targets.len().min(64)).max(1);
+    let mut pool = rayon::ThreadPoolBuilder::new().num_threads(parallel).build().unwrap();
+    let scrape_statistics = options.scrape_statistics;
+    let mut yugabyte_metrics: Vec<&str> = Vec::new();
+    if yb || percentiles || tui { yugabyte_metrics.extend(YUGABYTE_IO_METRICS) };
+    if rpc || percentiles { yugabyte_metrics.extend(YUGABYTE_RPC_METRICS) };
+    if ysql { yugabyte_metrics.extend(YUGABYTE_YSQL_METRICS) };
+    if ycql { yugabyte_metrics.extend(YUGABYTE_YCQL_METRICS) };
+    let metric_filter = options.metric_filter.then(|| yugabyte_metrics.join(","));
+    // without a selector, only the series of the metrics that the enabled modes use are read from prometheus.
+    // histograms and summaries are stored by prometheus as series with _bucket, _count and _sum added to the name.
+    let prometheus_selector = options.prometheus_selector.clone().unwrap_or_else(|| {
+        let mut metrics = yugabyte_metrics.clone();
+        if cpu || cpu_detail || tui { metrics.extend(NODE_EXPORTER_CPU_METRICS) };
+        if memory { metrics.extend(NODE_EXPORTER_MEMORY_METRICS) };
+        if pressure { metrics.extend(NODE_EXPORTER_PRESSURE_METRICS) };
+        if disk || tui { metrics.extend(NODE_EXPORTER_DISK_METRICS) };
+        if filesystem { metrics.extend(NODE_EXPORTER_FILESYSTEM_METRICS) };
+        if network { metrics.extend(NODE_EXPORTER_NETWORK_METRICS) };
+        if tcp { metrics.extend(NODE_EXPORTER_TCP_METRICS) };
+        format!("{{__name__=~\"({})(_bucket|_count|_sum)?\"}}", metrics.iter().unique().join("|"))
+    });
+    let interval = options.interval;
+    let lines_for_header = options.lines_for_header;
+    let graph = options.graph;
+
+    let graph_name_addition = match &options.graph_addition {
         Some(addition) => format!("_{}", addition),
         None => "".to_string(),
     };
     //let graph_name_addition = graph_name_addition_string.as_str();
 
-    if !cpu && !disk && !yb {
+    if !cpu && !cpu_detail && !memory && !pressure && !disk && !filesystem && !network && !tcp && !yb && !rpc && !ysql && !ycql && !percentiles && !tui {
         Opts::clap().print_help().unwrap();
         process::exit(0);
     }
+    if tui && (options.output != "text" || options.scrape_statistics) {
+        eprintln!("the full-screen view (--tui) cannot be combined with csv or jsonl output or with --scrape-statistics");
+        process::exit(1);
+    }
+    // the full-screen view replaces the text output.
+    let text = options.output == "text" && !tui;
+    let csv = options.output == "csv";
+    let jsonl = options.output == "jsonl";
+    if csv && (cpu_detail || memory || pressure || filesystem || network || tcp || rpc || ysql || ycql || percentiles) {
+        eprintln!("csv output is available for the cpu (-c), disk (-d) and yugabyte (-y) statistics only");
+        process::exit(1);
+    }
+    // the modes have different columns, so stdout can only hold a single mode and still be a valid csv file.
+    if csv && options.output_directory.is_none() && [cpu, disk, yb].iter().filter(|mode| **mode).count() > 1 {
+        eprintln!("csv output of more than one mode needs --output-directory, to write every mode to its own file");
+        process::exit(1);
+    }
+    // the csv writer of a mode is created when its first row is written.
+    let mut csv_writers: HashMap<&str, Box<dyn io::Write>> = HashMap::new();
+    let mut jsonl_writer: Box<dyn io::Write> = match &options.output_file {
+        Some(output_file) => Box::new(io::BufWriter::new(fs::OpenOptions::new().create(true).append(true).open(output_file).unwrap_or_else(|error| {
+            eprintln!("error opening {}: {}", output_file, error);
+            process::exit(1);
+        }))),
+        None => Box::new(io::stdout()),
+    };
     let mut host_presentation: BTreeMap<String, CpuPresentation> = BTreeMap::new();
+    let mut cpu_core_presentation: BTreeMap<String, CpuCorePresentation> = BTreeMap::new();
+    let mut memory_presentation: BTreeMap<String, MemoryPresentation> = BTreeMap::new();
+    let mut pressure_presentation: BTreeMap<String, PressurePresentation> = BTreeMap::new();
     let mut disk_presentation: BTreeMap<String, DiskPresentation> = BTreeMap::new();
+    let mut filesystem_presentation: BTreeMap<String, FilesystemPresentation> = BTreeMap::new();
+    let mut network_presentation: BTreeMap<String, NetworkPresentation> = BTreeMap::new();
+    let mut tcp_presentation: BTreeMap<String, TcpPresentation> = BTreeMap::new();
     let mut yugabyte_presentation: BTreeMap<String, YBIOPresentation> = BTreeMap::new();
+    let mut yugabyte_rpc_presentation: BTreeMap<String, YBRpcPresentation> = BTreeMap::new();
+    let mut yugabyte_ysql_presentation: BTreeMap<String, YBYsqlPresentation> = BTreeMap::new();
+    let mut yugabyte_ycql_presentation: BTreeMap<String, YBYcqlPresentation> = BTreeMap::new();
     let mut row_counter = 0;
     let cpu_history: Vec<CpuGraph> = Vec::new();
     let cpu_history_ref: Arc<Mutex<Vec<CpuGraph>>> = Arc::new(Mutex::new(cpu_history));
+    let memory_history: Vec<MemoryGraph> = Vec::new();
+    let memory_history_ref: Arc<Mutex<Vec<MemoryGraph>>> = Arc::new(Mutex::new(memory_history));
+    let pressure_history: Vec<PressureGraph> = Vec::new();
+    let pressure_history_ref: Arc<Mutex<Vec<PressureGraph>>> = Arc::new(Mutex::new(pressure_history));
     let disk_history: Vec<DiskGraph> = Vec::new();
     let disk_history_ref: Arc<Mutex<Vec<DiskGraph>>> = Arc::new(Mutex::new(disk_history));
+    let network_history: Vec<NetworkGraph> = Vec::new();
+    let network_history_ref: Arc<Mutex<Vec<NetworkGraph>>> = Arc::new(Mutex::new(network_history));
     let yugabyte_history: Vec<YBIOGraph> = Vec::new();
     let yugabyte_history_ref: Arc<Mutex<Vec<YBIOGraph>>> = Arc::new(Mutex::new(yugabyte_history));
 
     let cpu_history_ctrlc_clone = cpu_history_ref.clone();
+    let memory_history_ctrlc_clone = memory_history_ref.clone();
+    let pressure_history_ctrlc_clone = pressure_history_ref.clone();
     let disk_history_ctrlc_clone = disk_history_ref.clone();
+    let network_history_ctrlc_clone = network_history_ref.clone();
     let yugabyte_history_ctrlc_clone = yugabyte_history_ref.clone();
 
+    let graph_name_addition_loop = graph_name_addition.clone();
     ctrlc::set_handler(move || {
         if graph {
             draw_cpu(&cpu_history_ctrlc_clone, graph_name_addition.clone());
+            draw_memory(&memory_history_ctrlc_clone, graph_name_addition.clone());
+            draw_pressure(&pressure_history_ctrlc_clone, graph_name_addition.clone());
             draw_disk(&disk_history_ctrlc_clone, graph_name_addition.clone());
+            draw_network(&network_history_ctrlc_clone, graph_name_addition.clone());
             draw_yugabyte(&yugabyte_history_ctrlc_clone, graph_name_addition.clone());
+            draw_yugabyte_latency(&yugabyte_history_ctrlc_clone, graph_name_addition.clone());
         }
         process::exit(0);
     }).unwrap();
 
     let cpu_history_loop_clone = cpu_history_ref.clone();
+    let memory_history_loop_clone = memory_history_ref.clone();
+    let pressure_history_loop_clone = pressure_history_ref.clone();
     let disk_history_loop_clone = disk_history_ref.clone();
+    let network_history_loop_clone = network_history_ref.clone();
     let yugabyte_history_loop_clone = yugabyte_history_ref.clone();
 
+    let mut memory_first_capture = true;
+    let mut pressure_first_capture = true;
     let mut disk_first_capture = true;
+    let mut network_first_capture = true;
     let mut ybio_first_capture = true;
+    let mut previously_unreachable: HashMap<String, String> = HashMap::new();
+    // the full-screen view starts with the first of the cpu, disk and yugabyte statistics that is specified.
+    let mut tui_state = TuiState {
+        view: if !cpu && disk { TuiView::Disk } else if !cpu && !disk && yb { TuiView::Yugabyte } else { TuiView::Cpu },
+        sort_column: 0,
+        descending: false,
+    };
+    let mut terminal = tui.then(ratatui::init);
+    // a past time window is read from prometheus at once, and then shown an interval at a time without waiting.
+    let parse_time = |time: &str| DateTime::parse_from_rfc3339(time).map(|time| time.with_timezone(&Utc)).unwrap_or_else(|error| {
+        eprintln!("invalid time {}: {}", time, error);
+        process::exit(1);
+    });
+    // a recording is replayed the same way, but is read an interval at a time.
+    let mut window: Option<Window> = match (&prometheus, &options.prometheus_start, &options.replay) {
+        (_, _, Some(replay)) => Some(read_recording(replay).map(|recording| Box::new(recording) as Box<dyn Iterator<Item = _>>)),
+        (Some(prometheus), Some(prometheus_start), None) => {
+            let start = parse_time(prometheus_start);
+            let end = options.prometheus_end.as_deref().map(parse_time).unwrap_or_else(Utc::now);
+            Some(read_prometheus_range(prometheus, &prometheus_selector, start, end, interval)
+                .map(|window| Box::new(window.into_iter().map(|node_values| Ok((node_values, HashMap::new())))) as Box<dyn Iterator<Item = _>>))
+        },
+        _ => None,
+    }.transpose().unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        process::exit(1);
+    }).map(Iterator::peekable);
+    let mut record_file = options.record.as_ref().map(|record| fs::OpenOptions::new().create(true).append(true).open(record).map(io::BufWriter::new)).transpose().unwrap_or_else(|error| {
+        eprintln!("error opening recording {}: {}", options.record.as_ref().unwrap(), error);
+        process::exit(1);
+    });
+    // if a role is given for any target, or any endpoint of the first interval of a window, the hostname column shows it.
+    let hostname_header = if targets.iter().any(|target| target.role.is_some())
+        || window.as_mut().and_then(|window| window.peek()).and_then(|interval| interval.as_ref().ok()).is_some_and(|(node_values, unreachable)| node_values.keys().chain(unreachable.keys()).any(|hostname_port| hostname_port.contains('@'))) {
+        "role@hostname"
+    } else {
+        "hostname"
+    };
+    // the role makes the hostname longer, so the modes with a hostname column of 30 get a wider one.
+    let hostname_width = if hostname_header == "role@hostname" { 50 } else { 30 };
+    let mut prometheus_fetch_times: HashMap<String, DateTime<Utc>> = HashMap::new();
     loop {
-        if row_counter == 0 && lines_for_header != 0 {
-            print_header(cpu, disk, yb);
+        if row_counter == 0 && lines_for_header != 0 && text {
+            print_header(&options, hostname_header, hostname_width);
         }
         let start_time = time::Instant::now();
-        let node_values = read_node_exporter_into_map(hosts, ports, 1);
+        // the cluster is discovered again periodically, so that added tablet servers are shown and removed ones are not.
+        // the masters found the previous time are tried too, in case the given master is down.
+        if let Some(master) = &master {
+            if discovery_time.elapsed() >= time::Duration::from_secs(options.master_refresh) {
+                let masters: Vec<Target> = std::iter::once(master.clone())
+                    .chain(targets.iter().filter(|target| target.role.as_deref() == Some("master")).cloned())
+                    .collect();
+                if let Ok(discovered_targets) = discover_cluster(&masters, &node_exporter) {
+                    targets = discovered_targets;
+                    let discovered_parallel = options.parallel.unwrap_or_else(|| targets.len().min(64)).max(1);
+                    if discovered_parallel != parallel {
+                        parallel = discovered_parallel;
+                        pool = rayon::ThreadPoolBuilder::new().num_threads(parallel).build().unwrap();
+                    }
+                }
+                discovery_time = time::Instant::now();
+            }
+        }
+        let (node_values, unreachable, endpoint_statistics) = match (&prometheus, &mut window) {
+            (_, Some(window)) => match window.next() {
+                Some(Ok((node_values, unreachable))) => (node_values, unreachable, HashMap::new()),
+                Some(Err(error)) => {
+                    if tui { ratatui::restore() };
+                    eprintln!("{}", error);
+                    process::exit(1);
+                },
+                None => {
+                    if tui { ratatui::restore() };
+                    if graph {
+                        draw_cpu(&cpu_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_memory(&memory_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_pressure(&pressure_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_disk(&disk_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_network(&network_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_yugabyte(&yugabyte_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_yugabyte_latency(&yugabyte_history_loop_clone, graph_name_addition_loop.clone());
+                    }
+                    process::exit(0);
+                },
+            },
+            (Some(prometheus), None) => match read_prometheus_into_map(prometheus, &prometheus_selector, (interval * 2).max(60)) {
+                Ok(mut node_values) => {
+                    // an endpoint that is not scraped again by prometheus since the previous time keeps showing its previous statistics.
+                    let fetch_times: HashMap<String, DateTime<Utc>> = node_values.iter().filter_map(|(hostname_port, values)| values.first().map(|value| (hostname_port.clone(), value.node_exporter_timestamp))).collect();
+                    node_values.retain(|hostname_port, _| prometheus_fetch_times.get(hostname_port) != fetch_times.get(hostname_port));
+                    prometheus_fetch_times = fetch_times;
+                    (node_values, HashMap::new(), HashMap::new())
+                },
+                Err(error) => (HashMap::new(), prometheus_fetch_times.keys().map(|hostname_port| (hostname_port.clone(), error.clone())).collect(), HashMap::new()),
+            },
+            (None, None) => {
+                discover_endpoints(&mut targets, &pool);
+                read_node_exporter_into_map(&targets, &pool, metric_filter.as_deref())
+            },
+        };
+        let read_duration = start_time.elapsed();
+        if let Some(record_file) = &mut record_file {
+            if let Err(error) = record_scrapes(record_file, &node_values, &unreachable) {
+                if tui { ratatui::restore() };
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+        }
+        // an endpoint that is reachable again starts over, so that no rate is calculated over the time it was unreachable,
+        // and an endpoint that is no longer a target, such as a removed tablet server, is not shown anymore.
+        let target_endpoints: HashSet<String> = match (&prometheus, &window) {
+            (None, None) => targets.iter().filter_map(|target| target.endpoint.as_ref().map(|endpoint| format!("{}:{}", target_hostname_port(target), endpoint))).collect(),
+            _ => prometheus_fetch_times.keys().chain(node_values.keys()).chain(unreachable.keys()).cloned().collect(),
+        };
+        let start_over = |key: &String| {
+            let hostname_port = key.split_whitespace().next().unwrap();
+            (previously_unreachable.contains_key(hostname_port) && !unreachable.contains_key(hostname_port)) || !target_endpoints.contains(hostname_port)
+        };
+        // the rows of an endpoint that is reachable again have no previous sample, so these are marked as reset until the next interval.
+        let rebaselined: HashSet<String> = previously_unreachable.keys().filter(|hostname_port| !unreachable.contains_key(*hostname_port)).cloned().collect();
+        host_presentation.retain(|key, _| !start_over(key));
+        cpu_core_presentation.retain(|key, _| !start_over(key));
+        memory_presentation.retain(|key, _| !start_over(key));
+        pressure_presentation.retain(|key, _| !start_over(key));
+        disk_presentation.retain(|key, _| !start_over(key));
+        filesystem_presentation.retain(|key, _| !start_over(key));
+        network_presentation.retain(|key, _| !start_over(key));
+        tcp_presentation.retain(|key, _| !start_over(key));
+        yugabyte_presentation.retain(|key, _| !start_over(key));
+        yugabyte_rpc_presentation.retain(|key, _| !start_over(key));
+        yugabyte_ysql_presentation.retain(|key, _| !start_over(key));
+        yugabyte_ycql_presentation.retain(|key, _| !start_over(key));
 
         let cpu_details = cpu_details(&node_values);
         diff_cpu_details(cpu_details, &mut host_presentation);
+        mark_rebaselined(&mut host_presentation, &rebaselined, |row| row.reset = true);
         for (hostname_port, row) in &host_presentation {
-            if !(row.user_diff == 0. && row.system_diff == 0. && row.iowait_diff == 0. && row.nice_diff == 0. && row.irq_diff == 0. && row.softirq_diff == 0. && row.steal_diff == 0.) && graph {
+            if unreachable.contains_key(hostname_port.split_whitespace().next().unwrap()) { continue };
+            if (row.reset || !(row.user_diff == 0. && row.system_diff == 0. && row.iowait_diff == 0. && row.nice_diff == 0. && row.irq_diff == 0. && row.softirq_diff == 0. && row.steal_diff == 0.)) && (graph || tui) {
                 let mut cpu_history = cpu_history_loop_clone.lock().unwrap();
                 cpu_history.push(CpuGraph {
                     hostname: hostname_port.to_string(),
@@ -179,43 +636,184 @@ fn main() {
                     idle: row.user_diff + row.system_diff + row.iowait_diff + row.nice_diff + row.irq_diff + row.softirq_diff + row.steal_diff + row.idle_diff,
                     scheduler_runtime: row.schedstat_running_diff,
                     scheduler_wait: row.schedstat_running_diff + row.schedstat_waiting_diff,
+                    reset: row.reset,
                 });
             };
-            if cpu {
-                println!("{:30} {:5.0} {:5.0} | {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} | {:7.3} {:7.3} | {:7.3} {:7.3} | {:7.0} {:7.0} | {:6.3} {:6.3} {:6.3}",
+            if row.reset {
+                if cpu && text {
+                    row_counter += print_counter_reset(hostname_port, hostname_width, &rebaselined);
+                }
+                continue;
+            }
+            if cpu && csv {
+                write_csv(&mut csv_writers, &options.output_directory, "cpu",
+                          &["timestamp", "hostname", "procs_running", "procs_blocked", "idle", "user", "system", "iowait", "nice", "irq", "softirq", "steal", "guest_user", "guest_nice", "schedstat_running", "schedstat_waiting", "interrupts", "context_switches", "load_1", "load_5", "load_15"],
+                          &[row.timestamp.to_rfc3339(), hostname_port.to_string()],
+                          &[row.procs_running, row.procs_blocked, row.idle_diff, row.user_diff, row.system_diff, row.iowait_diff, row.nice_diff, row.irq_diff, row.softirq_diff, row.steal_diff, row.guest_user_diff, row.guest_nice_diff, row.schedstat_running_diff, row.schedstat_waiting_diff, row.interrupts_diff, row.context_switches_diff, row.load_1, row.load_5, row.load_15],
+                );
+            } else if cpu && text {
+                print_cpu_row(hostname_port, row, hostname_width);
+                row_counter += 1;
+            }
+        }
+        if cpu && text {
+            row_counter += print_unreachable(&host_presentation, &unreachable, hostname_width);
+            if let Some(cluster_total) = cpu_cluster_total(&host_presentation, &unreachable) {
+                print_cpu_row("cluster", &cluster_total, hostname_width);
+                row_counter += 1;
+            }
+        }
+        if cpu_detail {
+            let cpu_core_details = cpu_core_details(&node_values);
+            diff_cpu_core_details(cpu_core_details, &mut cpu_core_presentation);
+            mark_rebaselined(&mut cpu_core_presentation, &rebaselined, |row| row.reset = true);
+        }
+        if cpu_detail && text {
+            for hostname_port in cpu_core_presentation.keys().map(|x| x.split_whitespace().next().unwrap()).unique().filter(|x| !unreachable.contains_key(*x)) {
+                // busy time is all time that is not idle or iowait, iowait is idle time too.
+                let cores = cpu_core_presentation
+                    .iter()
+                    .filter(|(host_cpu, _)| host_cpu.split_whitespace().next().unwrap() == hostname_port);
+                let cores: Vec<(&String, &CpuCorePresentation)> = match cpu_top {
+                    Some(number_of_cpus) => cores
+                        .sorted_by(|(_, a), (_, b)| (b.user_diff + b.nice_diff + b.system_diff + b.irq_diff + b.softirq_diff + b.steal_diff).total_cmp(&(a.user_diff + a.nice_diff + a.system_diff + a.irq_diff + a.softirq_diff + a.steal_diff)))
+                        .take(number_of_cpus)
+                        .collect(),
+                    None => cores
+                        .sorted_by_key(|(host_cpu, _)| host_cpu.rsplit("cpu").next().unwrap_or_default().parse::<usize>().unwrap_or_default())
+                        .collect(),
+                };
+                for (host_cpu, row) in cores {
+                    if row.reset {
+                        row_counter += print_counter_reset(host_cpu, hostname_width + 10, &rebaselined);
+                        continue;
+                    }
+                    println!("{:cpu_width$} | {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} | {:7.3}",
+                             host_cpu,
+                             row.idle_diff,
+                             row.user_diff,
+                             row.system_diff,
+                             row.iowait_diff,
+                             row.nice_diff,
+                             row.irq_diff,
+                             row.softirq_diff,
+                             row.steal_diff,
+                             row.user_diff + row.nice_diff + row.system_diff + row.irq_diff + row.softirq_diff + row.steal_diff,
+                             cpu_width = hostname_width + 10,
+                    );
+                    row_counter += 1;
+                }
+            }
+        }
+        if cpu_detail && text {
+            row_counter += print_unreachable(&cpu_core_presentation, &unreachable, hostname_width + 10);
+        }
+        let memory_details = memory_details(&node_values);
+        diff_memory_details(memory_details, &mut memory_presentation);
+        mark_rebaselined(&mut memory_presentation, &rebaselined, |row| row.reset = true);
+        for (hostname_port, row) in &memory_presentation {
+            if unreachable.contains_key(hostname_port.split_whitespace().next().unwrap()) { continue };
+            if !memory_first_capture && graph {
+                let mut memory_history = memory_history_loop_clone.lock().unwrap();
+                memory_history.push(MemoryGraph {
+                    hostname: hostname_port.to_string(),
+                    timestamp: row.timestamp,
+                    anon_pages: row.anon_pages,
+                    slab: row.anon_pages + row.slab,
+                    buffers: row.anon_pages + row.slab + row.buffers,
+                    cached: row.anon_pages + row.slab + row.buffers + row.cached,
+                    free: row.anon_pages + row.slab + row.buffers + row.cached + row.mem_free,
+                    total: row.mem_total,
+                    pgpgin: row.pgpgin_diff,
+                    pgpgout: row.pgpgout_diff,
+                    pswpin: row.pswpin_diff,
+                    pswpout: row.pswpout_diff,
+                    pgmajfault: row.pgmajfault_diff,
+                    reset: row.reset,
+                });
+            }
+            if row.reset {
+                if memory && text {
+                    row_counter += print_counter_reset(hostname_port, hostname_width, &rebaselined);
+                }
+                continue;
+            }
+            if memory && text {
+                println!("{:hostname_width$} {:8.0} {:8.0} {:8.0} {:8.0} | {:8.0} {:8.0} | {:8.0} {:8.0} | {:8.0} | {:7.0} {:7.0} | {:7.0} {:7.0} | {:7.0}",
+                         hostname_port,
+                         row.mem_total / (1024. * 1024.),
+                         row.mem_free / (1024. * 1024.),
+                         row.buffers / (1024. * 1024.),
+                         row.cached / (1024. * 1024.),
+                         row.dirty / (1024. * 1024.),
+                         row.writeback / (1024. * 1024.),
+                         row.anon_pages / (1024. * 1024.),
+                         row.slab / (1024. * 1024.),
+                         row.swap_free / (1024. * 1024.),
+                         row.pgpgin_diff,
+                         row.pgpgout_diff,
+                         row.pswpin_diff,
+                         row.pswpout_diff,
+                         row.pgmajfault_diff,
+                );
+                row_counter += 1;
+            }
+        }
+        if memory && text {
+            row_counter += print_unreachable(&memory_presentation, &unreachable, hostname_width);
+        }
+        memory_first_capture = false;
+        let pressure_details = pressure_details(&node_values);
+        diff_pressure_details(pressure_details, &mut pressure_presentation);
+        mark_rebaselined(&mut pressure_presentation, &rebaselined, |row| row.reset = true);
+        for (hostname_port, row) in &pressure_presentation {
+            if unreachable.contains_key(hostname_port.split_whitespace().next().unwrap()) { continue };
+            if !pressure_first_capture && graph {
+                let mut pressure_history = pressure_history_loop_clone.lock().unwrap();
+                pressure_history.push(PressureGraph {
+                    hostname: hostname_port.to_string(),
+                    timestamp: row.timestamp,
+                    cpu_waiting: row.cpu_waiting_diff,
+                    io_waiting: row.cpu_waiting_diff + row.io_waiting_diff,
+                    memory_waiting: row.cpu_waiting_diff + row.io_waiting_diff + row.memory_waiting_diff,
+                    io_stalled: row.io_stalled_diff,
+                    memory_stalled: row.memory_stalled_diff,
+                    reset: row.reset,
+                });
+            }
+            if row.reset {
+                if pressure && text {
+                    row_counter += print_counter_reset(hostname_port, hostname_width, &rebaselined);
+                }
+                continue;
+            }
+            if pressure && text {
+                println!("{:hostname_width$} {:7.3} | {:7.3} {:7.3} | {:7.3} {:7.3}",
                          hostname_port,
-                         row.procs_running,
-                         row.procs_blocked,
-                         row.idle_diff,
-                         row.user_diff,
-                         row.system_diff,
-                         row.iowait_diff,
-                         row.nice_diff,
-                         row.irq_diff,
-                         row.softirq_diff,
-                         row.steal_diff,
-                         row.guest_user_diff,
-                         row.guest_nice_diff,
-                         row.schedstat_running_diff,
-                         row.schedstat_waiting_diff,
-                         row.interrupts_diff,
-                         row.context_switches_diff,
-                         row.load_1,
-                         row.load_5,
-                         row.load_15,
+                         row.cpu_waiting_diff,
+                         row.io_waiting_diff,
+                         row.io_stalled_diff,
+                         row.memory_waiting_diff,
+                         row.memory_stalled_diff,
                 );
                 row_counter += 1;
             }
         }
+        if pressure && text {
+            row_counter += print_unreachable(&pressure_presentation, &unreachable, hostname_width);
+        }
+        pressure_first_capture = false;
         let disk_details = disk_details(&node_values);
         diff_disk_details(disk_details, &mut disk_presentation);
+        mark_rebaselined(&mut disk_presentation, &rebaselined, |row| row.reset = true);
         for (host_disk, row) in &disk_presentation {
+            if unreachable.contains_key(host_disk.split_whitespace().next().unwrap()) { continue };
             if disk_first_capture && graph {
                 disk_first_capture = false;
             } else {
                 let mut disk_history = disk_history_loop_clone.lock().unwrap();
                 disk_history.push(DiskGraph {
-                    hostname: host_disk.split_whitespace().nth(0).unwrap().to_string(),
+                    hostname: host_disk.split_whitespace().next().unwrap().to_string(),
                     timestamp: row.timestamp,
                     disk: host_disk.split_whitespace().nth(1).unwrap().to_string(),
                     //reads_merged: row.reads_merged_diff,
@@ -227,45 +825,155 @@ fn main() {
                     writes_bytes: row.writes_bytes_diff,
                     writes_time: row.writes_time_diff,
                     queue: row.queue_diff,
+                    reset: row.reset,
                 });
             }
-            if disk {
-                println!("{:50} {:5.0} {:5.0} {:5.0} {:8.6} | {:5.0} {:5.0} {:5.0} {:8.6} | {:5.0} {:5.0} {:5.0} {:8.6} | {:8.3} | {:5.0} {:5.0}",
-                         host_disk,
-                         row.reads_merged_diff.round(),
-                         row.reads_completed_diff.round(),
-                         (row.reads_bytes_diff / (1024 * 1024) as f64).round(),
-                         if (row.reads_time_diff / row.reads_completed_diff).is_nan() {
-                             0.0
+            if row.reset {
+                if disk && text {
+                    row_counter += print_counter_reset(host_disk, 50, &rebaselined);
+                }
+                continue;
+            }
+            if disk && csv {
+                write_csv(&mut csv_writers, &options.output_directory, "disk",
+                          &["timestamp", "hostname", "device", "reads_completed", "reads_merged", "reads_bytes", "reads_time", "writes_completed", "writes_merged", "writes_bytes", "writes_time", "discards_completed", "discards_merged", "discards_sectors", "discards_time", "disk_total_time", "queue"],
+                          &[row.timestamp.to_rfc3339(), host_disk.split_whitespace().next().unwrap().to_string(), host_disk.split_whitespace().nth(1).unwrap().to_string()],
+                          &[row.reads_completed_diff, row.reads_merged_diff, row.reads_bytes_diff, row.reads_time_diff, row.writes_completed_diff, row.writes_merged_diff, row.writes_bytes_diff, row.writes_time_diff, row.discards_completed_diff, row.discards_merged_diff, row.discards_sectors_diff, row.discards_time_diff, row.disk_total_time_diff, row.queue_diff],
+                );
+            } else if disk && text {
+                print_disk_row(host_disk, row);
+                row_counter += 1;
+            }
+        }
+        if disk && text {
+            row_counter += print_unreachable(&disk_presentation, &unreachable, 50);
+            if let Some(cluster_total) = disk_cluster_total(&disk_presentation, &unreachable) {
+                print_disk_row("cluster", &cluster_total);
+                row_counter += 1;
+            }
+        }
+        let filesystem_details = filesystem_details(&node_values);
+        diff_filesystem_details(filesystem_details, &mut filesystem_presentation);
+        mark_rebaselined(&mut filesystem_presentation, &rebaselined, |row| row.reset = true);
+        if filesystem && text {
+            for (host_mountpoint, row) in filesystem_presentation.iter().filter(|(host_mountpoint, _)| !unreachable.contains_key(host_mountpoint.split_whitespace().next().unwrap())).filter(|(host_mountpoint, _)| mountpoints.is_empty() || mountpoints.contains(&host_mountpoint.split_once(' ').map(|(_, mountpoint)| mountpoint).unwrap_or_default())) {
+                if row.reset {
+                    row_counter += print_counter_reset(host_mountpoint, 50, &rebaselined);
+                    continue;
+                }
+                println!("{:50} {:20} {:8} {:9.1} {:9.1} {:6.1} {:6.1} | {:8.2}",
+                         host_mountpoint,
+                         row.device,
+                         row.fstype,
+                         row.size_bytes / (1024. * 1024. * 1024.),
+                         row.avail_bytes_counter / (1024. * 1024. * 1024.),
+                         if row.size_bytes > 0. {
+                             (row.size_bytes - row.avail_bytes_counter) / row.size_bytes * 100.
                          } else {
-                             row.reads_time_diff / row.reads_completed_diff
+                             0.
+                         },
+                         if row.files > 0. {
+                             (row.files - row.files_free) / row.files * 100.
+                         } else {
+                             0.
                          },
-                         row.writes_merged_diff.round(),
-                         row.writes_completed_diff.round(),
-                         (row.writes_bytes_diff / (1024 * 1024) as f64).round(),
-                         if (row.writes_time_diff / row.writes_completed_diff).is_nan() {
-                             0.0
+                         // a decrease of the available space is the rate at which the filesystem fills up.
+                         (0. - row.avail_bytes_diff) / (1024. * 1024.),
+                );
+                row_counter += 1;
+            }
+        }
+        if filesystem && text {
+            row_counter += print_unreachable(&filesystem_presentation, &unreachable, 50);
+        }
+        let network_details = network_details(&node_values);
+        diff_network_details(network_details, &mut network_presentation);
+        mark_rebaselined(&mut network_presentation, &rebaselined, |row| row.reset = true);
+        for (host_interface, row) in &network_presentation {
+            if unreachable.contains_key(host_interface.split_whitespace().next().unwrap()) { continue };
+            if !network_first_capture && graph {
+                let mut network_history = network_history_loop_clone.lock().unwrap();
+                network_history.push(NetworkGraph {
+                    hostname: host_interface.split_whitespace().next().unwrap().to_string(),
+                    timestamp: row.timestamp,
+                    interface: host_interface.split_whitespace().nth(1).unwrap().to_string(),
+                    receive_bytes: row.receive_bytes_diff,
+                    transmit_bytes: row.transmit_bytes_diff,
+                    receive_packets: row.receive_packets_diff,
+                    transmit_packets: row.transmit_packets_diff,
+                    reset: row.reset,
+                });
+            }
+            if row.reset {
+                if network && text {
+                    row_counter += print_counter_reset(host_interface, 50, &rebaselined);
+                }
+                continue;
+            }
+            if network && text {
+                println!("{:50} {:8.2} {:8.2} | {:8.0} {:8.0} | {:6.1} {:6.1} | {:6.1} {:6.1} | {:6.1}",
+                         host_interface,
+                         row.receive_bytes_diff / (1024. * 1024.),
+                         row.transmit_bytes_diff / (1024. * 1024.),
+                         row.receive_packets_diff,
+                         row.transmit_packets_diff,
+                         row.receive_errs_diff,
+                         row.transmit_errs_diff,
+                         row.receive_drop_diff,
+                         row.transmit_drop_diff,
+                         // network interfaces are full duplex, so the busiest direction determines the utilisation.
+                         if row.speed_bytes > 0. {
+                             row.receive_bytes_diff.max(row.transmit_bytes_diff) / row.speed_bytes * 100.
                          } else {
-                             row.writes_time_diff / row.writes_completed_diff
+                             0.
                          },
-                         row.discards_merged_diff.round(),
-                         row.discards_completed_diff.round(),
-                         row.discards_sectors_diff.round(),
-                         if (row.discards_time_diff / row.discards_completed_diff).is_nan() {
-                             0.0
+                );
+                row_counter += 1;
+            }
+        }
+        if network && text {
+            row_counter += print_unreachable(&network_presentation, &unreachable, 50);
+        }
+        network_first_capture = false;
+        let tcp_details = tcp_details(&node_values);
+        diff_tcp_details(tcp_details, &mut tcp_presentation);
+        mark_rebaselined(&mut tcp_presentation, &rebaselined, |row| row.reset = true);
+        if tcp && text {
+            for (hostname_port, row) in &tcp_presentation {
+                if unreachable.contains_key(hostname_port.split_whitespace().next().unwrap()) { continue };
+                if row.reset {
+                    row_counter += print_counter_reset(hostname_port, hostname_width, &rebaselined);
+                    continue;
+                }
+                println!("{:hostname_width$} {:8.0} {:8.0} {:7.1} {:6.2} | {:7.1} {:7.1} {:7.1} | {:7.0} {:7.0} {:7.0}",
+                         hostname_port,
+                         row.in_segs_diff,
+                         row.out_segs_diff,
+                         row.retrans_segs_diff,
+                         // with no segments sent the division is NaN or infinite, so the retransmit percentage is 0.
+                         if row.out_segs_diff > 0. {
+                             row.retrans_segs_diff / row.out_segs_diff * 100.
                          } else {
-                             row.discards_time_diff / row.discards_completed_diff
+                             0.
                          },
-                         row.queue_diff,
-                         (row.reads_completed_diff + row.writes_completed_diff).round(),
-                         (row.reads_bytes_diff / (1024 * 1024) as f64 + row.writes_bytes_diff / (1024 * 1024) as f64).round(),
+                         row.listen_overflows_diff,
+                         row.listen_drops_diff,
+                         row.tcp_timeouts_diff,
+                         row.tcp_inuse,
+                         row.tcp_tw,
+                         row.tcp_alloc,
                 );
                 row_counter += 1;
             }
         }
+        if tcp && text {
+            row_counter += print_unreachable(&tcp_presentation, &unreachable, hostname_width);
+        }
         let yugabyte_details = yugabyte_details(&node_values);
         diff_yugabyte_details(yugabyte_details, &mut yugabyte_presentation);
+        mark_rebaselined(&mut yugabyte_presentation, &rebaselined, |row| row.reset = true);
         for (hostname_port, row) in &yugabyte_presentation {
+            if unreachable.contains_key(hostname_port.split_whitespace().next().unwrap()) { continue };
             if ybio_first_capture && graph {
                 ybio_first_capture = false;
             } else {
@@ -289,60 +997,733 @@ fn main() {
                     rocksdb_write_raw_block_micros_sum: row.rocksdb_write_raw_block_micros_sum_diff,
                     rocksdb_sst_read_micros_count: row.rocksdb_sst_read_micros_count_diff,
                     rocksdb_sst_read_micros_sum: row.rocksdb_sst_read_micros_sum_diff,
+                    log_append_latency_p50: row.log_append_latency_p50,
+                    log_append_latency_p95: row.log_append_latency_p95,
+                    log_append_latency_p99: row.log_append_latency_p99,
+                    log_sync_latency_p50: row.log_sync_latency_p50,
+                    log_sync_latency_p95: row.log_sync_latency_p95,
+                    log_sync_latency_p99: row.log_sync_latency_p99,
+                    rocksdb_sst_read_micros_p50: row.rocksdb_sst_read_micros_p50,
+                    rocksdb_sst_read_micros_p95: row.rocksdb_sst_read_micros_p95,
+                    rocksdb_sst_read_micros_p99: row.rocksdb_sst_read_micros_p99,
+                    rocksdb_write_raw_block_micros_p50: row.rocksdb_write_raw_block_micros_p50,
+                    rocksdb_write_raw_block_micros_p95: row.rocksdb_write_raw_block_micros_p95,
+                    rocksdb_write_raw_block_micros_p99: row.rocksdb_write_raw_block_micros_p99,
+                    reset: row.reset,
                 });
             }
-            if yb {
-                println!("{:50} {:7.2} {:7.2} | {:7.2} {:7.2} {:7.2} {:7.2} {:7.2} {:7.2} | {:7.0} {:7.0} {:7.0} | {:10.2} {:7.2} {:10.2} {:7.2}",
+            if row.reset {
+                if yb && text {
+                    row_counter += print_counter_reset(hostname_port, 50, &rebaselined);
+                }
+                continue;
+            }
+            if yb && csv {
+                write_csv(&mut csv_writers, &options.output_directory, "yugabyte",
+                          &["timestamp", "hostname", "glog_messages_info", "glog_messages_prio", "log_bytes_logged", "log_reader_bytes_read", "log_append_latency_count", "log_append_latency_sum", "log_cache_disk_reads", "log_sync_latency_count", "log_sync_latency_sum", "rocksdb_flush_write_bytes", "rocksdb_compact_read_bytes", "rocksdb_compact_write_bytes", "rocksdb_sst_read_micros_count", "rocksdb_sst_read_micros_sum", "rocksdb_write_raw_block_micros_count", "rocksdb_write_raw_block_micros_sum", "log_append_latency_p50", "log_append_latency_p95", "log_append_latency_p99", "log_sync_latency_p50", "log_sync_latency_p95", "log_sync_latency_p99", "rocksdb_sst_read_micros_p50", "rocksdb_sst_read_micros_p95", "rocksdb_sst_read_micros_p99", "rocksdb_write_raw_block_micros_p50", "rocksdb_write_raw_block_micros_p95", "rocksdb_write_raw_block_micros_p99"],
+                          &[row.timestamp.to_rfc3339(), hostname_port.to_string()],
+                          &[row.glog_messages_info_diff, row.glog_messages_prio_diff, row.log_bytes_logged_diff, row.log_reader_bytes_read_diff, row.log_append_latency_count_diff, row.log_append_latency_sum_diff, row.log_cache_disk_reads_diff, row.log_sync_latency_count_diff, row.log_sync_latency_sum_diff, row.rocksdb_flush_write_bytes_diff, row.rocksdb_compact_read_bytes_diff, row.rocksdb_compact_write_bytes_diff, row.rocksdb_sst_read_micros_count_diff, row.rocksdb_sst_read_micros_sum_diff, row.rocksdb_write_raw_block_micros_count_diff, row.rocksdb_write_raw_block_micros_sum_diff, row.log_append_latency_p50, row.log_append_latency_p95, row.log_append_latency_p99, row.log_sync_latency_p50, row.log_sync_latency_p95, row.log_sync_latency_p99, row.rocksdb_sst_read_micros_p50, row.rocksdb_sst_read_micros_p95, row.rocksdb_sst_read_micros_p99, row.rocksdb_write_raw_block_micros_p50, row.rocksdb_write_raw_block_micros_p95, row.rocksdb_write_raw_block_micros_p99],
+                );
+            } else if yb && text {
+                print_yugabyte_row(hostname_port, row);
+                row_counter += 1;
+            }
+        }
+        if yb && text {
+            row_counter += print_unreachable(&yugabyte_presentation, &unreachable, 50);
+            if let Some(cluster_total) = yugabyte_cluster_total(&yugabyte_presentation, &unreachable) {
+                print_yugabyte_row("cluster", &cluster_total);
+                row_counter += 1;
+            }
+        }
+
+        let yugabyte_rpc_details = yugabyte_rpc_details(&node_values);
+        diff_yugabyte_rpc_details(yugabyte_rpc_details, &mut yugabyte_rpc_presentation);
+        mark_rebaselined(&mut yugabyte_rpc_presentation, &rebaselined, |row| row.reset = true);
+        if rpc && text {
+            for (hostname_port, row) in &yugabyte_rpc_presentation {
+                if unreachable.contains_key(hostname_port.split_whitespace().next().unwrap()) { continue };
+                if row.reset {
+                    row_counter += print_counter_reset(hostname_port, 50, &rebaselined);
+                    continue;
+                }
+                println!("{:50} {:9.0} {:8.3} | {:9.0} {:8.3} | {:9.0} {:8.3} | {:9.0} {:8.3}",
                          hostname_port,
-                         row.glog_messages_info_diff,
-                         row.glog_messages_prio_diff,
-                         row.log_bytes_logged_diff / (1024. * 1024.),
-                         row.log_reader_bytes_read_diff / (1024. * 1024.),
-                         row.log_append_latency_count_diff,
-                         if ((row.log_append_latency_sum_diff / row.log_append_latency_count_diff) / 1000.).is_nan() {
+                         row.tserver_read_count_diff,
+                         if !((row.tserver_read_sum_diff / row.tserver_read_count_diff) / 1000.).is_finite() {
                              0.
                          } else {
-                             (row.log_append_latency_sum_diff / row.log_append_latency_count_diff) / 1000.
+                             (row.tserver_read_sum_diff / row.tserver_read_count_diff) / 1000.
                          },
-                         row.log_cache_disk_reads_diff,
-                         if ((row.log_sync_latency_sum_diff / row.log_sync_latency_count_diff) / 1000.).is_nan() {
+                         row.tserver_write_count_diff,
+                         if !((row.tserver_write_sum_diff / row.tserver_write_count_diff) / 1000.).is_finite() {
                              0.
                          } else {
-                             (row.log_sync_latency_sum_diff / row.log_sync_latency_count_diff) / 1000.
+                             (row.tserver_write_sum_diff / row.tserver_write_count_diff) / 1000.
                          },
-                         row.rocksdb_flush_write_bytes_diff / (1024. * 1024.),
-                         row.rocksdb_compact_read_bytes_diff / (1024. * 1024.),
-                         row.rocksdb_compact_write_bytes_diff / (1024. * 1024.),
-                         row.rocksdb_sst_read_micros_count_diff,
-                         if ((row.rocksdb_sst_read_micros_sum_diff / row.rocksdb_sst_read_micros_count_diff) / 1000.).is_nan() {
+                         row.consensus_update_count_diff,
+                         if !((row.consensus_update_sum_diff / row.consensus_update_count_diff) / 1000.).is_finite() {
                              0.
                          } else {
-                             (row.rocksdb_sst_read_micros_sum_diff / row.rocksdb_sst_read_micros_count_diff) / 1000.
+                             (row.consensus_update_sum_diff / row.consensus_update_count_diff) / 1000.
                          },
-                         row.rocksdb_write_raw_block_micros_count_diff,
-                         if ((row.rocksdb_write_raw_block_micros_sum_diff / row.rocksdb_write_raw_block_micros_count_diff) / 1000.).is_nan() {
+                         row.rpc_incoming_queue_time_count_diff,
+                         if !((row.rpc_incoming_queue_time_sum_diff / row.rpc_incoming_queue_time_count_diff) / 1000.).is_finite() {
                              0.
                          } else {
-                             (row.rocksdb_write_raw_block_micros_sum_diff / row.rocksdb_write_raw_block_micros_count_diff) / 1000.
+                             (row.rpc_incoming_queue_time_sum_diff / row.rpc_incoming_queue_time_count_diff) / 1000.
                          },
                 );
                 row_counter += 1;
             }
         }
+        if rpc && text {
+            row_counter += print_unreachable(&yugabyte_rpc_presentation, &unreachable, 50);
+        }
+
+        let yugabyte_ysql_details = yugabyte_ysql_details(&node_values);
+        diff_yugabyte_ysql_details(yugabyte_ysql_details, &mut yugabyte_ysql_presentation);
+        mark_rebaselined(&mut yugabyte_ysql_presentation, &rebaselined, |row| row.reset = true);
+        if ysql && text {
+            for (hostname_port, row) in &yugabyte_ysql_presentation {
+                if unreachable.contains_key(hostname_port.split_whitespace().next().unwrap()) { continue };
+                if row.reset {
+                    row_counter += print_counter_reset(hostname_port, 50, &rebaselined);
+                    continue;
+                }
+                println!("{:50} {:9.0} {:8.3} | {:9.0} {:8.3} | {:9.0} {:8.3} | {:9.0} {:8.3} | {:9.0} {:8.3} | {:9.0}",
+                         hostname_port,
+                         row.select_count_diff,
+                         if !((row.select_sum_diff / row.select_count_diff) / 1000.).is_finite() {
+                             0.
+                         } else {
+                             (row.select_sum_diff / row.select_count_diff) / 1000.
+                         },
+                         row.insert_count_diff,
+                         if !((row.insert_sum_diff / row.insert_count_diff) / 1000.).is_finite() {
+                             0.
+                         } else {
+                             (row.insert_sum_diff / row.insert_count_diff) / 1000.
+                         },
+                         row.update_count_diff,
+                         if !((row.update_sum_diff / row.update_count_diff) / 1000.).is_finite() {
+                             0.
+                         } else {
+                             (row.update_sum_diff / row.update_count_diff) / 1000.
+                         },
+                         row.delete_count_diff,
+                         if !((row.delete_sum_diff / row.delete_count_diff) / 1000.).is_finite() {
+                             0.
+                         } else {
+                             (row.delete_sum_diff / row.delete_count_diff) / 1000.
+                         },
+                         row.transactions_count_diff,
+                         if !((row.transactions_sum_diff / row.transactions_count_diff) / 1000.).is_finite() {
+                             0.
+                         } else {
+                             (row.transactions_sum_diff / row.transactions_count_diff) / 1000.
+                         },
+                         row.catalog_cache_misses_count_diff,
+                );
+                row_counter += 1;
+            }
+        }
+        if ysql && text {
+            row_counter += print_unreachable(&yugabyte_ysql_presentation, &unreachable, 50);
+        }
+        let yugabyte_ycql_details = yugabyte_ycql_details(&node_values);
+        diff_yugabyte_ycql_details(yugabyte_ycql_details, &mut yugabyte_ycql_presentation);
+        mark_rebaselined(&mut yugabyte_ycql_presentation, &rebaselined, |row| row.reset = true);
+        if ycql && text {
+            for (hostname_port, row) in &yugabyte_ycql_presentation {
+                if unreachable.contains_key(hostname_port.split_whitespace().next().unwrap()) { continue };
+                if row.reset {
+                    row_counter += print_counter_reset(hostname_port, 50, &rebaselined);
+                    continue;
+                }
+                println!("{:50} {:7.0} {:7.2} {:7.0} {:7.2} | {:7.0} {:7.2} {:7.0} {:7.2} | {:7.0} {:7.2}",
+                         hostname_port,
+                         row.select_count_diff,
+                         if !((row.select_sum_diff / row.select_count_diff) / 1000.).is_finite() {
+                             0.
+                         } else {
+                             (row.select_sum_diff / row.select_count_diff) / 1000.
+                         },
+                         row.insert_count_diff,
+                         if !((row.insert_sum_diff / row.insert_count_diff) / 1000.).is_finite() {
+                             0.
+                         } else {
+                             (row.insert_sum_diff / row.insert_count_diff) / 1000.
+                         },
+                         row.execute_request_count_diff,
+                         if !((row.execute_request_sum_diff / row.execute_request_count_diff) / 1000.).is_finite() {
+                             0.
+                         } else {
+                             (row.execute_request_sum_diff / row.execute_request_count_diff) / 1000.
+                         },
+                         row.parse_request_count_diff,
+                         if !((row.parse_request_sum_diff / row.parse_request_count_diff) / 1000.).is_finite() {
+                             0.
+                         } else {
+                             (row.parse_request_sum_diff / row.parse_request_count_diff) / 1000.
+                         },
+                         row.cql_processors_alive,
+                         row.cql_processors_created_diff,
+                );
+                row_counter += 1;
+            }
+        }
+        if ycql && text {
+            row_counter += print_unreachable(&yugabyte_ycql_presentation, &unreachable, 50);
+        }
+        if percentiles && text {
+            // the average latency is calculated from _sum and _count, the percentiles from the histogram buckets or summary quantiles.
+            for (hostname_port, row) in &yugabyte_presentation {
+                if unreachable.contains_key(hostname_port.split_whitespace().next().unwrap()) { continue };
+                if row.reset {
+                    row_counter += print_counter_reset(hostname_port, 50, &rebaselined);
+                    continue;
+                }
+                for (latency_metric, count_diff, sum_diff, p50, p95, p99) in [
+                    ("log_append_latency", row.log_append_latency_count_diff, row.log_append_latency_sum_diff, row.log_append_latency_p50, row.log_append_latency_p95, row.log_append_latency_p99),
+                    ("log_sync_latency", row.log_sync_latency_count_diff, row.log_sync_latency_sum_diff, row.log_sync_latency_p50, row.log_sync_latency_p95, row.log_sync_latency_p99),
+                    ("rocksdb_sst_read_micros", row.rocksdb_sst_read_micros_count_diff, row.rocksdb_sst_read_micros_sum_diff, row.rocksdb_sst_read_micros_p50, row.rocksdb_sst_read_micros_p95, row.rocksdb_sst_read_micros_p99),
+                    ("rocksdb_write_raw_block_micros", row.rocksdb_write_raw_block_micros_count_diff, row.rocksdb_write_raw_block_micros_sum_diff, row.rocksdb_write_raw_block_micros_p50, row.rocksdb_write_raw_block_micros_p95, row.rocksdb_write_raw_block_micros_p99),
+                ] {
+                    println!("{:50} {:32} | {:9.0} {:8.3} {:8.3} {:8.3} {:8.3}",
+                             hostname_port,
+                             latency_metric,
+                             count_diff,
+                             if !((sum_diff / count_diff) / 1000.).is_finite() { 0. } else { (sum_diff / count_diff) / 1000. },
+                             p50 / 1000.,
+                             p95 / 1000.,
+                             p99 / 1000.,
+                    );
+                    row_counter += 1;
+                }
+            }
+            for (hostname_port, row) in &yugabyte_rpc_presentation {
+                if unreachable.contains_key(hostname_port.split_whitespace().next().unwrap()) { continue };
+                if row.reset {
+                    row_counter += print_counter_reset(hostname_port, 50, &rebaselined);
+                    continue;
+                }
+                for (latency_metric, count_diff, sum_diff, p50, p95, p99) in [
+                    ("TabletServerService_Read", row.tserver_read_count_diff, row.tserver_read_sum_diff, row.tserver_read_p50, row.tserver_read_p95, row.tserver_read_p99),
+                    ("TabletServerService_Write", row.tserver_write_count_diff, row.tserver_write_sum_diff, row.tserver_write_p50, row.tserver_write_p95, row.tserver_write_p99),
+                    ("ConsensusService_UpdateConsensus", row.consensus_update_count_diff, row.consensus_update_sum_diff, row.consensus_update_p50, row.consensus_update_p95, row.consensus_update_p99),
+                    ("rpc_incoming_queue_time", row.rpc_incoming_queue_time_count_diff, row.rpc_incoming_queue_time_sum_diff, row.rpc_incoming_queue_time_p50, row.rpc_incoming_queue_time_p95, row.rpc_incoming_queue_time_p99),
+                ] {
+                    println!("{:50} {:32} | {:9.0} {:8.3} {:8.3} {:8.3} {:8.3}",
+                             hostname_port,
+                             latency_metric,
+                             count_diff,
+                             if !((sum_diff / count_diff) / 1000.).is_finite() { 0. } else { (sum_diff / count_diff) / 1000. },
+                             p50 / 1000.,
+                             p95 / 1000.,
+                             p99 / 1000.,
+                    );
+                    row_counter += 1;
+                }
+            }
+            row_counter += print_unreachable(&yugabyte_presentation, &unreachable, 50);
+            row_counter += print_unreachable(&yugabyte_rpc_presentation, &unreachable, 50);
+        }
+
+        if jsonl {
+            if cpu { write_jsonl(&mut jsonl_writer, "cpu", &host_presentation, &unreachable) };
+            if cpu_detail { write_jsonl(&mut jsonl_writer, "cpu_detail", &cpu_core_presentation, &unreachable) };
+            if memory { write_jsonl(&mut jsonl_writer, "memory", &memory_presentation, &unreachable) };
+            if pressure { write_jsonl(&mut jsonl_writer, "pressure", &pressure_presentation, &unreachable) };
+            if disk { write_jsonl(&mut jsonl_writer, "disk", &disk_presentation, &unreachable) };
+            if filesystem { write_jsonl(&mut jsonl_writer, "filesystem", &filesystem_presentation, &unreachable) };
+            if network { write_jsonl(&mut jsonl_writer, "network", &network_presentation, &unreachable) };
+            if tcp { write_jsonl(&mut jsonl_writer, "tcp", &tcp_presentation, &unreachable) };
+            if yb || percentiles { write_jsonl(&mut jsonl_writer, "yugabyte", &yugabyte_presentation, &unreachable) };
+            if rpc || percentiles { write_jsonl(&mut jsonl_writer, "yugabyte_rpc", &yugabyte_rpc_presentation, &unreachable) };
+            if ysql { write_jsonl(&mut jsonl_writer, "yugabyte_ysql", &yugabyte_ysql_presentation, &unreachable) };
+            if ycql { write_jsonl(&mut jsonl_writer, "yugabyte_ycql", &yugabyte_ycql_presentation, &unreachable) };
+            jsonl_writer.flush().unwrap_or_else(|error| {
+                eprintln!("error writing jsonl output: {}", error);
+                process::exit(1);
+            });
+        }
+
+        if scrape_statistics {
+            println!("{:50} {:>9} {:>9}", "endpoint", "scrape ms", "size KB");
+            for (hostname_port, statistics) in endpoint_statistics.iter().sorted_by(|a, b| a.0.cmp(b.0)) {
+                if unreachable.contains_key(hostname_port) {
+                    println!("{:50} {:9.0} unreachable", hostname_port, statistics.duration.as_secs_f64() * 1000.);
+                } else {
+                    println!("{:50} {:9.0} {:9.0}", hostname_port, statistics.duration.as_secs_f64() * 1000., statistics.size as f64 / 1024.);
+                }
+            }
+            // the time all endpoints took together, which is what the interval is extended with if it exceeds the interval.
+            println!("{:50} {:9.0}", "total", read_duration.as_secs_f64() * 1000.);
+            row_counter += endpoint_statistics.len() as u64 + 2;
+        }
 
         if row_counter > lines_for_header {
             row_counter = 0;
         }
-        if start_time.elapsed() < time::Duration::from_secs(interval) {
-            thread::sleep(time::Duration::from_secs(interval) - start_time.elapsed());
+        for csv_writer in csv_writers.values_mut() {
+            csv_writer.flush().unwrap_or_else(|error| {
+                eprintln!("error writing csv output: {}", error);
+                process::exit(1);
+            });
+        }
+        previously_unreachable = unreachable;
+        match &mut terminal {
+            Some(terminal) => {
+                // the view is drawn again after every key, until the interval has passed.
+                loop {
+                    let (columns, sparkline_header, rows) = match tui_state.view {
+                        TuiView::Cpu => (&TUI_CPU_COLUMNS[..], "busy", cpu_tui_rows(&host_presentation, &cpu_history_loop_clone.lock().unwrap(), &previously_unreachable, options.tui_samples)),
+                        TuiView::Disk => (&TUI_DISK_COLUMNS[..], "IOPS", disk_tui_rows(&disk_presentation, &disk_history_loop_clone.lock().unwrap(), &previously_unreachable, options.tui_samples)),
+                        TuiView::Yugabyte => (&TUI_YUGABYTE_COLUMNS[..], "log WMB", yugabyte_tui_rows(&yugabyte_presentation, &yugabyte_history_loop_clone.lock().unwrap(), &previously_unreachable, options.tui_samples)),
+                    };
+                    draw_tui(terminal, &tui_state, hostname_header, columns, sparkline_header, options.tui_samples, rows);
+                    let remaining = time::Duration::from_secs(interval).saturating_sub(start_time.elapsed());
+                    if remaining.is_zero() || !event::poll(remaining).unwrap_or(false) { break };
+                    let key = match event::read() {
+                        Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+                        _ => continue,
+                    };
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => {},
+                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {},
+                        KeyCode::Char(view @ ('c' | 'd' | 'y')) => {
+                            tui_state = TuiState {
+                                view: match view { 'c' => TuiView::Cpu, 'd' => TuiView::Disk, _ => TuiView::Yugabyte },
+                                sort_column: 0,
+                                descending: false,
+                            };
+                            continue;
+                        },
+                        KeyCode::Left => {
+                            tui_state.sort_column = tui_state.sort_column.saturating_sub(1);
+                            continue;
+                        },
+                        KeyCode::Right => {
+                            tui_state.sort_column = (tui_state.sort_column + 1).min(columns.len());
+                            continue;
+                        },
+                        KeyCode::Char('r') => {
+                            tui_state.descending = !tui_state.descending;
+                            continue;
+                        },
+                        _ => continue,
+                    };
+                    // quit, the same way as with ctrl-c when the terminal is not in raw mode.
+                    ratatui::restore();
+                    if graph {
+                        draw_cpu(&cpu_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_memory(&memory_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_pressure(&pressure_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_disk(&disk_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_network(&network_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_yugabyte(&yugabyte_history_loop_clone, graph_name_addition_loop.clone());
+                        draw_yugabyte_latency(&yugabyte_history_loop_clone, graph_name_addition_loop.clone());
+                    }
+                    process::exit(0);
+                }
+            },
+            None => {
+                if window.is_none() && start_time.elapsed() < time::Duration::from_secs(interval) {
+                    thread::sleep(time::Duration::from_secs(interval) - start_time.elapsed());
+                }
+            },
         }
     }
 }
 
-fn print_header(cpu: bool, disk: bool, yb: bool) {
-    if cpu {
-        println!("{:30} {:>5} {:>5} | {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} | {:>7} {:>7} | {:>7} {:>7} | {:>7} {:>7} | {:>6} {:>6} {:>6}",
-                 "hostname",
+enum TuiView {
+    Cpu,
+    Disk,
+    Yugabyte,
+}
+
+struct TuiState {
+    view: TuiView,
+    // column 0 is the hostname, the other columns are the statistics.
+    sort_column: usize,
+    descending: bool,
+}
+
+struct TuiRow {
+    key: String,
+    values: Vec<f64>,
+    sparkline: String,
+    unreachable: bool,
+}
+
+// the columns of the full-screen view with their number of decimals, the same as the text output.
+const TUI_CPU_COLUMNS: [(&str, usize); 19] = [("r", 0), ("b", 0), ("id", 3), ("us", 3), ("sy", 3), ("io", 3), ("ni", 3), ("ir", 3), ("si", 3), ("st", 3), ("gu", 3), ("gn", 3), ("scd_rt", 3), ("scd_wt", 3), ("in", 0), ("cs", 0), ("l_1", 3), ("l_5", 3), ("l_15", 3)];
+const TUI_DISK_COLUMNS: [(&str, usize); 15] = [("r merge", 0), ("r io", 0), ("r mb", 0), ("r avg", 6), ("w merge", 0), ("w io", 0), ("w mb", 0), ("w avg", 6), ("d merge", 0), ("d io", 0), ("d sect", 0), ("d avg", 6), ("queue", 3), ("IOPS", 0), ("MBPS", 0)];
+const TUI_YUGABYTE_COLUMNS: [(&str, usize); 15] = [("msgWinf", 2), ("msgWpri", 2), ("log WMB", 2), ("log RMB", 2), ("log WIO", 2), ("Wlat ms", 2), ("log RIO", 2), ("lat syn", 2), ("fls WMB", 0), ("cmp RMB", 0), ("cmp WMB", 0), ("rdb RIO", 2), ("Rlat ms", 2), ("rdb WIO", 2), ("Wlat ms", 2)];
+
+fn sparkline(values: &[f64]) -> String {
+    // the bars are scaled to the highest value of the samples.
+    let bars = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().cloned().fold(0., f64::max);
+    values.iter().map(|value| if max > 0. { bars[((value / max) * 7.).round().clamp(0., 7.) as usize] } else { bars[0] }).collect()
+}
+
+fn last_samples(values: Vec<f64>, samples: usize) -> Vec<f64> {
+    values[values.len().saturating_sub(samples)..].to_vec()
+}
+
+fn average(sum: f64, count: f64) -> f64 {
+    if !(sum / count).is_finite() { 0. } else { sum / count }
+}
+
+fn cpu_tui_rows(
+    host_presentation: &BTreeMap<String, CpuPresentation>,
+    cpu_history: &[CpuGraph],
+    unreachable: &HashMap<String, String>,
+    samples: usize,
+) -> Vec<TuiRow> {
+    host_presentation.iter().filter(|(_, row)| !row.reset).map(|(hostname_port, row)| TuiRow {
+        key: hostname_port.to_string(),
+        values: vec![row.procs_running, row.procs_blocked, row.idle_diff, row.user_diff, row.system_diff, row.iowait_diff, row.nice_diff, row.irq_diff, row.softirq_diff, row.steal_diff, row.guest_user_diff, row.guest_nice_diff, row.schedstat_running_diff, row.schedstat_waiting_diff, row.interrupts_diff, row.context_switches_diff, row.load_1, row.load_5, row.load_15],
+        // the graph values are stacked, busy time is all time that is not idle or iowait.
+        sparkline: sparkline(&last_samples(cpu_history.iter().filter(|cpu| &cpu.hostname == hostname_port && !cpu.reset).map(|cpu| cpu.steal - cpu.iowait + cpu.system).collect(), samples)),
+        unreachable: unreachable.contains_key(hostname_port.as_str()),
+    }).collect()
+}
+
+fn disk_tui_rows(
+    disk_presentation: &BTreeMap<String, DiskPresentation>,
+    disk_history: &[DiskGraph],
+    unreachable: &HashMap<String, String>,
+    samples: usize,
+) -> Vec<TuiRow> {
+    disk_presentation.iter().filter(|(_, row)| !row.reset).map(|(host_disk, row)| {
+        let (hostname_port, disk) = host_disk.split_once(' ').unwrap_or_default();
+        TuiRow {
+            key: host_disk.to_string(),
+            values: vec![
+                row.reads_merged_diff,
+                row.reads_completed_diff,
+                row.reads_bytes_diff / (1024. * 1024.),
+                average(row.reads_time_diff, row.reads_completed_diff),
+                row.writes_merged_diff,
+                row.writes_completed_diff,
+                row.writes_bytes_diff / (1024. * 1024.),
+                average(row.writes_time_diff, row.writes_completed_diff),
+                row.discards_merged_diff,
+                row.discards_completed_diff,
+                row.discards_sectors_diff,
+                average(row.discards_time_diff, row.discards_completed_diff),
+                row.queue_diff,
+                row.reads_completed_diff + row.writes_completed_diff,
+                (row.reads_bytes_diff + row.writes_bytes_diff) / (1024. * 1024.),
+            ],
+            sparkline: sparkline(&last_samples(disk_history.iter().filter(|history| history.hostname == hostname_port && history.disk == disk && !history.reset).map(|history| history.reads_completed + history.writes_completed).collect(), samples)),
+            unreachable: unreachable.contains_key(hostname_port),
+        }
+    }).collect()
+}
+
+fn yugabyte_tui_rows(
+    yugabyte_presentation: &BTreeMap<String, YBIOPresentation>,
+    yugabyte_history: &[YBIOGraph],
+    unreachable: &HashMap<String, String>,
+    samples: usize,
+) -> Vec<TuiRow> {
+    yugabyte_presentation.iter().filter(|(_, row)| !row.reset).map(|(hostname_port, row)| TuiRow {
+        key: hostname_port.to_string(),
+        values: vec![
+            row.glog_messages_info_diff,
+            row.glog_messages_prio_diff,
+            row.log_bytes_logged_diff / (1024. * 1024.),
+            row.log_reader_bytes_read_diff / (1024. * 1024.),
+            row.log_append_latency_count_diff,
+            average(row.log_append_latency_sum_diff, row.log_append_latency_count_diff) / 1000.,
+            row.log_cache_disk_reads_diff,
+            average(row.log_sync_latency_sum_diff, row.log_sync_latency_count_diff) / 1000.,
+            row.rocksdb_flush_write_bytes_diff / (1024. * 1024.),
+            row.rocksdb_compact_read_bytes_diff / (1024. * 1024.),
+            row.rocksdb_compact_write_bytes_diff / (1024. * 1024.),
+            row.rocksdb_sst_read_micros_count_diff,
+            average(row.rocksdb_sst_read_micros_sum_diff, row.rocksdb_sst_read_micros_count_diff) / 1000.,
+            row.rocksdb_write_raw_block_micros_count_diff,
+            average(row.rocksdb_write_raw_block_micros_sum_diff, row.rocksdb_write_raw_block_micros_count_diff) / 1000.,
+        ],
+        sparkline: sparkline(&last_samples(yugabyte_history.iter().filter(|yugabyte| &yugabyte.hostname == hostname_port && !yugabyte.reset).map(|yugabyte| yugabyte.log_bytes_logged / (1024. * 1024.)).collect(), samples)),
+        unreachable: unreachable.contains_key(hostname_port.as_str()),
+    }).collect()
+}
+
+fn draw_tui(
+    terminal: &mut DefaultTerminal,
+    tui_state: &TuiState,
+    hostname_header: &str,
+    columns: &[(&str, usize)],
+    sparkline_header: &str,
+    samples: usize,
+    mut rows: Vec<TuiRow>,
+) {
+    // unreachable rows are shown last, regardless of the sorting.
+    rows.sort_by(|a, b| {
+        let ordering = match tui_state.sort_column {
+            0 => a.key.cmp(&b.key),
+            column => a.values[column - 1].total_cmp(&b.values[column - 1]),
+        };
+        a.unreachable.cmp(&b.unreachable).then(if tui_state.descending { ordering.reverse() } else { ordering })
+    });
+    let sort_marker = if tui_state.descending { "▼" } else { "▲" };
+    let header = Row::new(
+        std::iter::once(hostname_header).chain(columns.iter().map(|(name, _)| *name)).enumerate().map(|(column, name)| {
+            let (name, style) = match column == tui_state.sort_column {
+                true => (format!("{}{}", name, sort_marker), Style::default().add_modifier(Modifier::REVERSED)),
+                false => (name.to_string(), Style::default()),
+            };
+            match column {
+                0 => Cell::from(name).style(style),
+                _ => Cell::from(Line::from(name).alignment(Alignment::Right)).style(style),
+            }
+        }).chain(std::iter::once(Cell::from(sparkline_header.to_string())))
+    ).style(Style::default().add_modifier(Modifier::BOLD));
+    let table_rows = rows.iter().map(|row| match row.unreachable {
+        true => Row::new([Cell::from(row.key.clone()), Cell::from("unreachable")]),
+        false => Row::new(
+            std::iter::once(Cell::from(row.key.clone()))
+                .chain(row.values.iter().zip(columns).map(|(value, (_, decimals))| Cell::from(Line::from(format!("{:.*}", *decimals, value)).alignment(Alignment::Right))))
+                .chain(std::iter::once(Cell::from(row.sparkline.clone())))
+        ),
+    });
+    // a column is as wide as its widest value or its header with the sort marker.
+    let widths: Vec<Constraint> = std::iter::once(rows.iter().map(|row| row.key.chars().count()).max().unwrap_or_default().max(hostname_header.len() + 1))
+        .chain(columns.iter().enumerate().map(|(column, (name, decimals))| rows.iter().filter_map(|row| row.values.get(column)).map(|value| format!("{:.*}", *decimals, value).len()).max().unwrap_or_default().max(name.len() + 1)))
+        .chain(std::iter::once(samples.max(sparkline_header.len())))
+        .map(|width| Constraint::Length(width as u16))
+        .collect();
+    let view = match tui_state.view {
+        TuiView::Cpu => "cpu",
+        TuiView::Disk => "disk",
+        TuiView::Yugabyte => "yugabyte",
+    };
+    let table = Table::new(table_rows, widths)
+        .header(header)
+        .block(Block::default().borders(Borders::ALL).title(format!(" nodetop {} | c cpu, d disk, y yugabyte, ←/→ sort column, r reverse, q quit ", view)));
+    terminal.draw(|frame| frame.render_widget(table, frame.area())).unwrap();
+}
+
+fn write_csv(
+    csv_writers: &mut HashMap<&str, Box<dyn io::Write>>,
+    output_directory: &Option<String>,
+    mode: &'static str,
+    header: &[&str],
+    fields: &[String],
+    values: &[f64],
+) {
+    // the header of a mode is written once, when its writer is created, which is a file named after the mode if an
+    // output directory is given.
+    let csv_writer = csv_writers.entry(mode).or_insert_with(|| {
+        let mut csv_writer: Box<dyn io::Write> = match output_directory {
+            Some(output_directory) => {
+                let file = format!("{}/{}.csv", output_directory, mode);
+                Box::new(io::BufWriter::new(fs::File::create(&file).unwrap_or_else(|error| {
+                    eprintln!("error creating {}: {}", file, error);
+                    process::exit(1);
+                })))
+            },
+            None => Box::new(io::stdout()),
+        };
+        writeln!(csv_writer, "{}", header.join(",")).unwrap_or_else(|error| {
+            eprintln!("error writing csv output: {}", error);
+            process::exit(1);
+        });
+        csv_writer
+    });
+    writeln!(csv_writer, "{}", fields.iter().cloned().chain(values.iter().map(|value| value.to_string())).join(",")).unwrap_or_else(|error| {
+        eprintln!("error writing csv output: {}", error);
+        process::exit(1);
+    });
+}
+
+// a jsonl object is a row of a presentation, with the target and the mode, and the device, such as a disk, if the mode
+// has a row per device.
+#[derive(Serialize)]
+struct JsonlRow<'a, T> {
+    target: &'a str,
+    mode: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    device: Option<&'a str>,
+    #[serde(flatten)]
+    row: &'a T,
+}
+
+fn write_jsonl<T: Serialize>(
+    jsonl_writer: &mut Box<dyn io::Write>,
+    mode: &str,
+    presentation: &BTreeMap<String, T>,
+    unreachable: &HashMap<String, String>,
+) {
+    for (key, row) in presentation {
+        let (target, device) = match key.split_once(' ') {
+            Some((target, device)) => (target, Some(device)),
+            None => (key.as_str(), None),
+        };
+        if unreachable.contains_key(target) { continue };
+        serde_json::to_writer(&mut *jsonl_writer, &JsonlRow { target, mode, device, row })
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(jsonl_writer))
+            .unwrap_or_else(|error| {
+                eprintln!("error writing jsonl output: {}", error);
+                process::exit(1);
+            });
+    }
+}
+
+fn print_cpu_row(
+    hostname: &str,
+    row: &CpuPresentation,
+    hostname_width: usize,
+) {
+    println!("{:hostname_width$} {:5.0} {:5.0} | {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} {:7.3} | {:7.3} {:7.3} | {:7.3} {:7.3} | {:7.0} {:7.0} | {:6.3} {:6.3} {:6.3}",
+             hostname,
+             row.procs_running,
+             row.procs_blocked,
+             row.idle_diff,
+             row.user_diff,
+             row.system_diff,
+             row.iowait_diff,
+             row.nice_diff,
+             row.irq_diff,
+             row.softirq_diff,
+             row.steal_diff,
+             row.guest_user_diff,
+             row.guest_nice_diff,
+             row.schedstat_running_diff,
+             row.schedstat_waiting_diff,
+             row.interrupts_diff,
+             row.context_switches_diff,
+             row.load_1,
+             row.load_5,
+             row.load_15,
+    );
+}
+
+fn print_disk_row(
+    hostname: &str,
+    row: &DiskPresentation,
+) {
+    println!("{:50} {:5.0} {:5.0} {:5.0} {:8.6} | {:5.0} {:5.0} {:5.0} {:8.6} | {:5.0} {:5.0} {:5.0} {:8.6} | {:8.3} | {:5.0} {:5.0}",
+             hostname,
+             row.reads_merged_diff.round(),
+             row.reads_completed_diff.round(),
+             (row.reads_bytes_diff / (1024 * 1024) as f64).round(),
+             if !(row.reads_time_diff / row.reads_completed_diff).is_finite() {
+                 0.0
+             } else {
+                 row.reads_time_diff / row.reads_completed_diff
+             },
+             row.writes_merged_diff.round(),
+             row.writes_completed_diff.round(),
+             (row.writes_bytes_diff / (1024 * 1024) as f64).round(),
+             if !(row.writes_time_diff / row.writes_completed_diff).is_finite() {
+                 0.0
+             } else {
+                 row.writes_time_diff / row.writes_completed_diff
+             },
+             row.discards_merged_diff.round(),
+             row.discards_completed_diff.round(),
+             row.discards_sectors_diff.round(),
+             if !(row.discards_time_diff / row.discards_completed_diff).is_finite() {
+                 0.0
+             } else {
+                 row.discards_time_diff / row.discards_completed_diff
+             },
+             row.queue_diff,
+             (row.reads_completed_diff + row.writes_completed_diff).round(),
+             (row.reads_bytes_diff / (1024 * 1024) as f64 + row.writes_bytes_diff / (1024 * 1024) as f64).round(),
+    );
+}
+
+fn print_yugabyte_row(
+    hostname: &str,
+    row: &YBIOPresentation,
+) {
+    println!("{:50} {:7.2} {:7.2} | {:7.2} {:7.2} {:7.2} {:7.2} {:7.2} {:7.2} | {:7.0} {:7.0} {:7.0} | {:10.2} {:7.2} {:10.2} {:7.2}",
+             hostname,
+             row.glog_messages_info_diff,
+             row.glog_messages_prio_diff,
+             row.log_bytes_logged_diff / (1024. * 1024.),
+             row.log_reader_bytes_read_diff / (1024. * 1024.),
+             row.log_append_latency_count_diff,
+             if !((row.log_append_latency_sum_diff / row.log_append_latency_count_diff) / 1000.).is_finite() {
+                 0.
+             } else {
+                 (row.log_append_latency_sum_diff / row.log_append_latency_count_diff) / 1000.
+             },
+             row.log_cache_disk_reads_diff,
+             if !((row.log_sync_latency_sum_diff / row.log_sync_latency_count_diff) / 1000.).is_finite() {
+                 0.
+             } else {
+                 (row.log_sync_latency_sum_diff / row.log_sync_latency_count_diff) / 1000.
+             },
+             row.rocksdb_flush_write_bytes_diff / (1024. * 1024.),
+             row.rocksdb_compact_read_bytes_diff / (1024. * 1024.),
+             row.rocksdb_compact_write_bytes_diff / (1024. * 1024.),
+             row.rocksdb_sst_read_micros_count_diff,
+             if !((row.rocksdb_sst_read_micros_sum_diff / row.rocksdb_sst_read_micros_count_diff) / 1000.).is_finite() {
+                 0.
+             } else {
+                 (row.rocksdb_sst_read_micros_sum_diff / row.rocksdb_sst_read_micros_count_diff) / 1000.
+             },
+             row.rocksdb_write_raw_block_micros_count_diff,
+             if !((row.rocksdb_write_raw_block_micros_sum_diff / row.rocksdb_write_raw_block_micros_count_diff) / 1000.).is_finite() {
+                 0.
+             } else {
+                 (row.rocksdb_write_raw_block_micros_sum_diff / row.rocksdb_write_raw_block_micros_count_diff) / 1000.
+             },
+    );
+}
+
+fn print_unreachable<T>(
+    presentation: &BTreeMap<String, T>,
+    unreachable: &HashMap<String, String>,
+    width: usize,
+) -> u64 {
+    // the presentation keys are hostname_port, or hostname_port and a device, such as a disk.
+    let mut rows = 0;
+    for hostname_port in presentation.keys().map(|x| x.split_whitespace().next().unwrap()).unique().filter(|x| unreachable.contains_key(*x)) {
+        println!("{:width$} unreachable", hostname_port, width = width);
+        rows += 1;
+    }
+    rows
+}
+
+fn print_counter_reset(
+    key: &str,
+    width: usize,
+    rebaselined: &HashSet<String>,
+) -> u64 {
+    // a row that is reset is started over, and shows no statistics until the next interval.
+    // the rows of an endpoint that is reachable again are started over the same way.
+    let reason = if rebaselined.contains(key.split_whitespace().next().unwrap()) { "re-baselined" } else { "counter reset" };
+    println!("{:width$} {}", key, reason, width = width);
+    1
+}
+
+fn mark_rebaselined<T>(
+    presentation: &mut BTreeMap<String, T>,
+    rebaselined: &HashSet<String>,
+    reset: fn(&mut T),
+) {
+    for (key, row) in presentation.iter_mut() {
+        if rebaselined.contains(key.split_whitespace().next().unwrap()) {
+            reset(row);
+        }
+    }
+}
+
+fn print_header(options: &Opts, hostname_header: &str, hostname_width: usize) {
+    if options.cpu {
+        println!("{:hostname_width$} {:>5} {:>5} | {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} | {:>7} {:>7} | {:>7} {:>7} | {:>7} {:>7} | {:>6} {:>6} {:>6}",
+                 hostname_header,
                  "r",
                  "b",
                  "id",
@@ -364,7 +1745,51 @@ fn print_header(cpu: bool, disk: bool, yb: bool) {
                  "l_15",
         );
     };
-    if disk {
+    if options.cpu_detail || options.cpu_top.is_some() {
+        println!("{:cpu_width$} | {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} {:>7} | {:>7}",
+                 format!("{} cpu", hostname_header),
+                 "id",
+                 "us",
+                 "sy",
+                 "io",
+                 "ni",
+                 "ir",
+                 "si",
+                 "st",
+                 "busy",
+                 cpu_width = hostname_width + 10,
+        );
+    };
+    if options.memory {
+        println!("{:hostname_width$} {:>8} {:>8} {:>8} {:>8} | {:>8} {:>8} | {:>8} {:>8} | {:>8} | {:>7} {:>7} | {:>7} {:>7} | {:>7}",
+                 hostname_header,
+                 "total",
+                 "free",
+                 "buff",
+                 "cache",
+                 "dirty",
+                 "wrtbck",
+                 "anon",
+                 "slab",
+                 "swpfree",
+                 "pgin",
+                 "pgout",
+                 "swpin",
+                 "swpout",
+                 "majflt",
+        );
+    };
+    if options.pressure {
+        println!("{:hostname_width$} {:>7} | {:>7} {:>7} | {:>7} {:>7}",
+                 hostname_header,
+                 "cpu_wt",
+                 "io_wt",
+                 "io_st",
+                 "mem_wt",
+                 "mem_st",
+        );
+    };
+    if options.disk {
         println!("{:50} {:26} | {:26} | {:26} | {:8} | {:11}",
                  "",
                  "reads per second",
@@ -374,7 +1799,7 @@ fn print_header(cpu: bool, disk: bool, yb: bool) {
                  "totals per second",
         );
         println!("{:50} {:>5} {:>5} {:>5} {:>8} | {:>5} {:>5} {:>5} {:>8} | {:>5} {:>5} {:>5} {:>8} | {:>8} | {:>5} {:>5}",
-                 "hostname",
+                 hostname_header,
                  "merge",
                  "io",
                  "mb",
@@ -392,9 +1817,58 @@ fn print_header(cpu: bool, disk: bool, yb: bool) {
                  "MBPS",
         );
     };
-    if yb {
+    if options.filesystem {
+        println!("{:50} {:20} {:8} {:>9} {:>9} {:>6} {:>6} | {:>8}",
+                 hostname_header,
+                 "device",
+                 "fstype",
+                 "size GB",
+                 "free GB",
+                 "used%",
+                 "inode%",
+                 "fill MBs",
+        );
+    };
+    if options.network {
+        println!("{:50} {:17} | {:17} | {:13} | {:13} | {:6}",
+                 "",
+                 "MB/sec",
+                 "packets/sec",
+                 "errors/sec",
+                 "drops/sec",
+                 "",
+        );
+        println!("{:50} {:>8} {:>8} | {:>8} {:>8} | {:>6} {:>6} | {:>6} {:>6} | {:>6}",
+                 hostname_header,
+                 "rx",
+                 "tx",
+                 "rx",
+                 "tx",
+                 "rx",
+                 "tx",
+                 "rx",
+                 "tx",
+                 "util%",
+        );
+    };
+    if options.tcp {
+        println!("{:hostname_width$} {:>8} {:>8} {:>7} {:>6} | {:>7} {:>7} {:>7} | {:>7} {:>7} {:>7}",
+                 hostname_header,
+                 "inseg",
+                 "outseg",
+                 "retrans",
+                 "retr%",
+                 "lstovf",
+                 "lstdrp",
+                 "tmouts",
+                 "inuse",
+                 "tw",
+                 "alloc",
+        );
+    };
+    if options.yb {
         println!("{:50} {:>7} {:>7} | {:7} {:7} {:>7} {:>7} {:>7} {:>7} | {:7} {:7} {:7} | {:>10} {:>7} {:>10} {:>7}",
-                 "hostname",
+                 hostname_header,
                  "msgWinf",
                  "msgWpri",
                  "log WMB",
@@ -412,6 +1886,109 @@ fn print_header(cpu: bool, disk: bool, yb: bool) {
                  "Wlat ms",
         );
     };
+    if options.rpc {
+        println!("{:50} {:>18} | {:>18} | {:>18} | {:>18}",
+                 "",
+                 "tserver read",
+                 "tserver write",
+                 "consensus update",
+                 "rpc incoming queue",
+        );
+        println!("{:50} {:>9} {:>8} | {:>9} {:>8} | {:>9} {:>8} | {:>9} {:>8}",
+                 hostname_header,
+                 "calls/s",
+                 "avg ms",
+                 "calls/s",
+                 "avg ms",
+                 "calls/s",
+                 "avg ms",
+                 "calls/s",
+                 "avg ms",
+        );
+    };
+    if options.ysql {
+        println!("{:50} {:>18} | {:>18} | {:>18} | {:>18} | {:>18} | {:>9}",
+                 "",
+                 "select",
+                 "insert",
+                 "update",
+                 "delete",
+                 "transactions",
+                 "catalog",
+        );
+        println!("{:50} {:>9} {:>8} | {:>9} {:>8} | {:>9} {:>8} | {:>9} {:>8} | {:>9} {:>8} | {:>9}",
+                 hostname_header,
+                 "stmts/s",
+                 "avg ms",
+                 "stmts/s",
+                 "avg ms",
+                 "stmts/s",
+                 "avg ms",
+                 "stmts/s",
+                 "avg ms",
+                 "txs/s",
+                 "avg ms",
+                 "misses/s",
+        );
+    };
+    if options.ycql {
+        println!("{:50} {:>7} {:>7} {:>7} {:>7} | {:>7} {:>7} {:>7} {:>7} | {:>7} {:>7}",
+                 hostname_header,
+                 "sel ops",
+                 "Slat ms",
+                 "ins ops",
+                 "Ilat ms",
+                 "exe ops",
+                 "Elat ms",
+                 "prs ops",
+                 "Plat ms",
+                 "proc",
+                 "proc cr",
+        );
+    };
+    if options.percentiles {
+        println!("{:50} {:32} | {:>9} {:>8} {:>8} {:>8} {:>8}",
+                 hostname_header,
+                 "latency metric",
+                 "calls/s",
+                 "avg ms",
+                 "p50 ms",
+                 "p95 ms",
+                 "p99 ms",
+        );
+    };
+}
+
+// a counter reset is stored in the graph data as a row with reset set.
+// the series is split at the resets into separate parts, so that a reset shows as a gap in the graph.
+fn area_segments<S: Into<ShapeStyle>>(
+    data: impl Iterator<Item = (DateTime<Utc>, f64, bool)>,
+    baseline: f64,
+    style: S,
+) -> Vec<Polygon<(DateTime<Utc>, f64)>> {
+    let style = style.into();
+    data.collect::<Vec<_>>()
+        .split(|(_, _, reset)| *reset)
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let mut points: Vec<(DateTime<Utc>, f64)> = segment.iter().map(|(timestamp, value, _)| (*timestamp, *value)).collect();
+            points.push((segment[segment.len() - 1].0, baseline));
+            points.push((segment[0].0, baseline));
+            Polygon::new(points, style.clone())
+        })
+        .collect()
+}
+
+fn line_segments<S: Into<ShapeStyle>>(
+    data: impl Iterator<Item = (DateTime<Utc>, f64, bool)>,
+    style: S,
+) -> Vec<PathElement<(DateTime<Utc>, f64)>> {
+    let style = style.into();
+    data.collect::<Vec<_>>()
+        .split(|(_, _, reset)| *reset)
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| PathElement::new(segment.iter().map(|(timestamp, value, _)| (*timestamp, *value)).collect::<Vec<_>>(), style.clone()))
+        .collect()
 }
 
 fn draw_cpu(data: &Arc<Mutex<Vec<CpuGraph>>>, graph_name_addition: String) {
@@ -422,8 +1999,8 @@ fn draw_cpu(data: &Arc<Mutex<Vec<CpuGraph>>>, graph_name_addition: String) {
     let start_time = cpu_data.iter().map(|x| x.timestamp).min().unwrap();
     let end_time = cpu_data.iter().map(|x| x.timestamp).max().unwrap();
     let low_value: f64 = 0.0;
-    let high_value_cpu = cpu_data.iter().map(|x| x.idle).fold(0. / 0., f64::max);
-    let high_value_scheduler = cpu_data.iter().map(|x| x.scheduler_wait).fold(0. / 0., f64::max);
+    let high_value_cpu = cpu_data.iter().map(|x| x.idle).fold(f64::NAN, f64::max);
+    let high_value_scheduler = cpu_data.iter().map(|x| x.scheduler_wait).fold(f64::NAN, f64::max);
     let high_value = if high_value_cpu > high_value_scheduler {
         high_value_cpu
     } else {
@@ -450,23 +2027,144 @@ fn draw_cpu(data: &Arc<Mutex<Vec<CpuGraph>>>, graph_name_addition: String) {
             .y_desc("Seconds per second")
             .draw()
             .unwrap();
-        context.draw_series(AreaSeries::new(cpu_data
+        context.draw_series(area_segments(cpu_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, x.scheduler_wait)), 0.0, Palette99::pick(1))
+                                                .map(|x| (x.timestamp, x.scheduler_wait, x.reset)), 0.0, Palette99::pick(1))
         )
             .unwrap()
             .label("scheduler wait")
             .legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(1).filled()));
-        context.draw_series(AreaSeries::new(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.scheduler_runtime)), 0.0, Palette99::pick(2))).unwrap().label("scheduler run").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(2).filled()));
-        context.draw_series(AreaSeries::new(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.steal)), 0.0, Palette99::pick(3))).unwrap().label("steal").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(3).filled()));
-        context.draw_series(AreaSeries::new(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.softirq)), 0.0, Palette99::pick(4))).unwrap().label("soft irq").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(4).filled()));
-        context.draw_series(AreaSeries::new(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.irq)), 0.0, Palette99::pick(5))).unwrap().label("irq").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(5).filled()));
-        context.draw_series(AreaSeries::new(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.nice)), 0.0, Palette99::pick(6))).unwrap().label("nice").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(6).filled()));
-        context.draw_series(AreaSeries::new(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.iowait)), 0.0, Palette99::pick(7))).unwrap().label("iowait").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(7).filled()));
-        context.draw_series(AreaSeries::new(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.system)), 0.0, Palette99::pick(8))).unwrap().label("system").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(8).filled()));
-        context.draw_series(AreaSeries::new(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.user)), 0.0, GREEN)).unwrap().label("user").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], GREEN.filled()));
-        context.draw_series(AreaSeries::new(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.idle)), 0.0, TRANSPARENT).border_style(RED)).unwrap().label("Total CPU").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], RED.filled()));
+        context.draw_series(area_segments(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.scheduler_runtime, x.reset)), 0.0, Palette99::pick(2))).unwrap().label("scheduler run").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(2).filled()));
+        context.draw_series(area_segments(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.steal, x.reset)), 0.0, Palette99::pick(3))).unwrap().label("steal").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(3).filled()));
+        context.draw_series(area_segments(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.softirq, x.reset)), 0.0, Palette99::pick(4))).unwrap().label("soft irq").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(4).filled()));
+        context.draw_series(area_segments(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.irq, x.reset)), 0.0, Palette99::pick(5))).unwrap().label("irq").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(5).filled()));
+        context.draw_series(area_segments(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.nice, x.reset)), 0.0, Palette99::pick(6))).unwrap().label("nice").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(6).filled()));
+        context.draw_series(area_segments(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.iowait, x.reset)), 0.0, Palette99::pick(7))).unwrap().label("iowait").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(7).filled()));
+        context.draw_series(area_segments(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.system, x.reset)), 0.0, Palette99::pick(8))).unwrap().label("system").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(8).filled()));
+        context.draw_series(area_segments(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.user, x.reset)), 0.0, GREEN)).unwrap().label("user").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], GREEN.filled()));
+        context.draw_series(line_segments(cpu_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.idle, x.reset)), RED)).unwrap().label("Total CPU").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], RED.filled()));
+        context.configure_series_labels().border_style(BLACK).background_style(WHITE.mix(0.7)).position(UpperLeft).draw().unwrap();
+    }
+}
+
+fn draw_memory(data: &Arc<Mutex<Vec<MemoryGraph>>>, graph_name_addition: String) {
+    let memory_data = data.lock().unwrap();
+
+    if memory_data.iter().count() == 0 { return };
+
+    let start_time = memory_data.iter().map(|x| x.timestamp).min().unwrap();
+    let end_time = memory_data.iter().map(|x| x.timestamp).max().unwrap();
+    let low_value_memory: f64 = 0.0;
+    let high_value_memory: f64 = memory_data.iter().map(|x| x.total / (1024. * 1024. * 1024.)).fold(f64::NAN, f64::max);
+    let low_value_paging: f64 = 0.0;
+    let high_value_paging: f64 = if memory_data.iter().map(|x| x.pgpgin + x.pgpgout + x.pswpin + x.pswpout + x.pgmajfault).fold(f64::NAN, f64::max) == 0. {
+        1.
+    } else {
+        memory_data.iter().map(|x| x.pgpgin + x.pgpgout + x.pswpin + x.pswpout + x.pgmajfault).fold(f64::NAN, f64::max)
+    };
+
+    let nr_servers = memory_data.iter().map(|x| x.hostname.clone()).unique().count();
+
+    let filename = format!("memory{}.png", graph_name_addition);
+    let root = BitMapBackend::new(&filename, (1200, ((nr_servers * 2) * 200).try_into().unwrap()))
+        .into_drawing_area();
+
+    let multiroot = root.split_evenly((nr_servers * 2, 1));
+    let mut multiroot_nr = 0;
+
+    for server in memory_data.iter().map(|x| x.hostname.clone()).unique() {
+        multiroot[multiroot_nr].fill(&WHITE).unwrap();
+        let mut context = ChartBuilder::on(&multiroot[multiroot_nr])
+            .x_label_area_size(60)
+            .y_label_area_size(50)
+            .right_y_label_area_size(50)
+            .caption(format!("{} {}", &server, "memory (GB)"), ("sans-serif", 20))
+            .build_cartesian_2d(start_time..end_time, low_value_memory..high_value_memory)
+            .unwrap();
+        context.configure_mesh()
+            .x_labels(4)
+            .x_label_formatter(&|x| x.to_rfc3339().to_string())
+            .y_desc("GB")
+            .draw()
+            .unwrap();
+        context.draw_series(area_segments(memory_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.free / (1024. * 1024. * 1024.), x.reset)), 0.0, Palette99::pick(1))).unwrap().label("free").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(1).filled()));
+        context.draw_series(area_segments(memory_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.cached / (1024. * 1024. * 1024.), x.reset)), 0.0, Palette99::pick(2))).unwrap().label("cached").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(2).filled()));
+        context.draw_series(area_segments(memory_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.buffers / (1024. * 1024. * 1024.), x.reset)), 0.0, Palette99::pick(3))).unwrap().label("buffers").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(3).filled()));
+        context.draw_series(area_segments(memory_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.slab / (1024. * 1024. * 1024.), x.reset)), 0.0, Palette99::pick(4))).unwrap().label("slab").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(4).filled()));
+        context.draw_series(area_segments(memory_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.anon_pages / (1024. * 1024. * 1024.), x.reset)), 0.0, GREEN)).unwrap().label("anonymous").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], GREEN.filled()));
+        context.draw_series(line_segments(memory_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.total / (1024. * 1024. * 1024.), x.reset)), RED)).unwrap().label("total memory").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], RED.filled()));
+        context.configure_series_labels().border_style(BLACK).background_style(WHITE.mix(0.7)).position(UpperLeft).draw().unwrap();
+
+        multiroot_nr += 1;
+
+        multiroot[multiroot_nr].fill(&WHITE).unwrap();
+        let mut context = ChartBuilder::on(&multiroot[multiroot_nr])
+            .x_label_area_size(60)
+            .y_label_area_size(50)
+            .right_y_label_area_size(50)
+            .caption(format!("{} {}", &server, "paging and swapping"), ("sans-serif", 20))
+            .build_cartesian_2d(start_time..end_time, low_value_paging..high_value_paging)
+            .unwrap();
+        context.configure_mesh()
+            .x_labels(4)
+            .x_label_formatter(&|x| x.to_rfc3339().to_string())
+            .y_desc("per second")
+            .draw()
+            .unwrap();
+        context.draw_series(line_segments(memory_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.pgpgin, x.reset)), GREEN)).unwrap().label("page in").legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], GREEN.filled()));
+        context.draw_series(line_segments(memory_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.pgpgout, x.reset)), BLUE)).unwrap().label("page out").legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], BLUE.filled()));
+        context.draw_series(line_segments(memory_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.pswpin, x.reset)), MAGENTA)).unwrap().label("swap in").legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], MAGENTA.filled()));
+        context.draw_series(line_segments(memory_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.pswpout, x.reset)), RED)).unwrap().label("swap out").legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], RED.filled()));
+        context.draw_series(line_segments(memory_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.pgmajfault, x.reset)), BLACK)).unwrap().label("major faults").legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], BLACK.filled()));
+        context.configure_series_labels().border_style(BLACK).background_style(WHITE.mix(0.7)).position(UpperLeft).draw().unwrap();
+
+        multiroot_nr += 1;
+    }
+}
+
+fn draw_pressure(data: &Arc<Mutex<Vec<PressureGraph>>>, graph_name_addition: String) {
+    let pressure_data = data.lock().unwrap();
+
+    if pressure_data.iter().count() == 0 { return };
+
+    let start_time = pressure_data.iter().map(|x| x.timestamp).min().unwrap();
+    let end_time = pressure_data.iter().map(|x| x.timestamp).max().unwrap();
+    let low_value: f64 = 0.0;
+    let high_value_waiting = pressure_data.iter().map(|x| x.memory_waiting).fold(f64::NAN, f64::max);
+    let high_value_stalled = pressure_data.iter().map(|x| x.io_stalled.max(x.memory_stalled)).fold(f64::NAN, f64::max);
+    let high_value = if high_value_waiting.max(high_value_stalled) == 0. {
+        1.
+    } else {
+        high_value_waiting.max(high_value_stalled)
+    };
+    let nr_servers = pressure_data.iter().map(|x| x.hostname.clone()).unique().count();
+
+    let filename = format!("pressure{}.png", graph_name_addition);
+    let root = BitMapBackend::new(&filename, (1200, (nr_servers * 200).try_into().unwrap()))
+        .into_drawing_area();
+    let multiroot = root.split_evenly((nr_servers, 1));
+
+    for (multiroot_nr, server) in (0..nr_servers).zip(pressure_data.iter().map(|x| x.hostname.clone()).unique()) {
+        multiroot[multiroot_nr].fill(&WHITE).unwrap();
+        let mut context = ChartBuilder::on(&multiroot[multiroot_nr])
+            .set_label_area_size(LabelAreaPosition::Left, 60)
+            .set_label_area_size(LabelAreaPosition::Bottom, 50)
+            .set_label_area_size(LabelAreaPosition::Right, 60)
+            .caption(&server, ("sans-serif", 20))
+            .build_cartesian_2d(start_time..end_time, low_value..high_value)
+            .unwrap();
+        context.configure_mesh()
+            .x_labels(4)
+            .x_label_formatter(&|x| x.to_rfc3339().to_string())
+            .y_desc("Seconds stalled per second")
+            .draw()
+            .unwrap();
+        context.draw_series(area_segments(pressure_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.memory_waiting, x.reset)), 0.0, Palette99::pick(1))).unwrap().label("memory waiting").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(1).filled()));
+        context.draw_series(area_segments(pressure_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.io_waiting, x.reset)), 0.0, Palette99::pick(2))).unwrap().label("io waiting").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(2).filled()));
+        context.draw_series(area_segments(pressure_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.cpu_waiting, x.reset)), 0.0, GREEN)).unwrap().label("cpu waiting").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], GREEN.filled()));
+        context.draw_series(line_segments(pressure_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.io_stalled, x.reset)), RED)).unwrap().label("io stalled").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], RED.filled()));
+        context.draw_series(line_segments(pressure_data.iter().filter(|x| x.hostname == server).map(|x| (x.timestamp, x.memory_stalled, x.reset)), BLACK)).unwrap().label("memory stalled").legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], BLACK.filled()));
         context.configure_series_labels().border_style(BLACK).background_style(WHITE.mix(0.7)).position(UpperLeft).draw().unwrap();
     }
 }
@@ -479,33 +2177,33 @@ fn draw_disk(data: &Arc<Mutex<Vec<DiskGraph>>>, graph_name_addition: String) {
     let start_time = disk_data.iter().map(|x| x.timestamp).min().unwrap();
     let end_time = disk_data.iter().map(|x| x.timestamp).max().unwrap();
     let low_value_iops: f64 = 0.0;
-    let high_value_iops: f64 = if disk_data.iter().map(|x| (x.reads_completed + x.writes_completed)).fold(0. / 0., f64::max) == 0. {
+    let high_value_iops: f64 = if disk_data.iter().map(|x| x.reads_completed + x.writes_completed).fold(f64::NAN, f64::max) == 0. {
         1.
     } else {
-        disk_data.iter().map(|x| (x.reads_completed + x.writes_completed)).fold(0. / 0., f64::max)
+        disk_data.iter().map(|x| x.reads_completed + x.writes_completed).fold(f64::NAN, f64::max)
     };
     let low_value_mbps: f64 = 0.;
-    let high_value_mbps: f64 = if disk_data.iter().map(|x| (x.reads_bytes + x.writes_bytes) / (1024. * 1024.)).fold(0. / 0., f64::max) == 0. {
+    let high_value_mbps: f64 = if disk_data.iter().map(|x| (x.reads_bytes + x.writes_bytes) / (1024. * 1024.)).fold(f64::NAN, f64::max) == 0. {
         1.
     } else {
-        disk_data.iter().map(|x| (x.reads_bytes + x.writes_bytes) / (1024. * 1024.)).fold(0. / 0., f64::max)
+        disk_data.iter().map(|x| (x.reads_bytes + x.writes_bytes) / (1024. * 1024.)).fold(f64::NAN, f64::max)
     };
     let low_value_queue = 0.;
-    let high_value_queue = if disk_data.iter().map(|x| x.queue).fold(0. / 0., f64::max) == 0. {
+    let high_value_queue = if disk_data.iter().map(|x| x.queue).fold(f64::NAN, f64::max) == 0. {
         1.
     } else {
-        disk_data.iter().map(|x| x.queue).fold(0. / 0., f64::max)
+        disk_data.iter().map(|x| x.queue).fold(f64::NAN, f64::max)
     };
     let low_value_latency = 0.;
-    let high_value_latency_read = if disk_data.iter().map(|x| (x.reads_time / x.reads_completed) * 1000.).fold(0. / 0., f64::max) == 0. {
+    let high_value_latency_read = if disk_data.iter().map(|x| (x.reads_time / x.reads_completed) * 1000.).fold(f64::NAN, f64::max) == 0. {
         1.
     } else {
-        disk_data.iter().map(|x| (x.reads_time / x.reads_completed) * 1000.).fold(0. / 0., f64::max)
+        disk_data.iter().map(|x| (x.reads_time / x.reads_completed) * 1000.).fold(f64::NAN, f64::max)
     };
-    let high_value_latency_write = if disk_data.iter().map(|x| (x.writes_time / x.writes_completed) * 1000.).fold(0. / 0., f64::max) == 0. {
+    let high_value_latency_write = if disk_data.iter().map(|x| (x.writes_time / x.writes_completed) * 1000.).fold(f64::NAN, f64::max) == 0. {
         1.
     } else {
-        disk_data.iter().map(|x| (x.writes_time / x.writes_completed) * 1000.).fold(0. / 0., f64::max)
+        disk_data.iter().map(|x| (x.writes_time / x.writes_completed) * 1000.).fold(f64::NAN, f64::max)
     };
     let high_value_latency = if high_value_latency_read > high_value_latency_write {
         high_value_latency_read
@@ -543,19 +2241,19 @@ fn draw_disk(data: &Arc<Mutex<Vec<DiskGraph>>>, graph_name_addition: String) {
                 .y_desc("IO per second")
                 .draw()
                 .unwrap();
-            context.draw_series(AreaSeries::new(disk_data
+            context.draw_series(area_segments(disk_data
                                                     .iter()
                                                     .filter(|x| x.hostname == server && x.disk == disk)
-                                                    .map(|x| (x.timestamp, (x.reads_completed + x.writes_completed)))
+                                                    .map(|x| (x.timestamp, (x.reads_completed + x.writes_completed), x.reset))
                                                 , 0.0, GREEN)
             )
                 .unwrap()
                 .label("read IOPS")
                 .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], GREEN.filled()));
-            context.draw_series(AreaSeries::new(disk_data
+            context.draw_series(area_segments(disk_data
                                                     .iter()
                                                     .filter(|x| x.hostname == server && x.disk == disk)
-                                                    .map(|x| (x.timestamp, (x.writes_completed))), 0.0, RED)
+                                                    .map(|x| (x.timestamp, (x.writes_completed), x.reset)), 0.0, RED)
             )
                 .unwrap()
                 .label("write IOPS")
@@ -584,18 +2282,18 @@ fn draw_disk(data: &Arc<Mutex<Vec<DiskGraph>>>, graph_name_addition: String) {
                 .y_desc("MB per second")
                 .draw()
                 .unwrap();
-            context.draw_series(AreaSeries::new(disk_data
+            context.draw_series(area_segments(disk_data
                                                     .iter()
                                                     .filter(|x| x.hostname == server && x.disk == disk)
-                                                    .map(|x| (x.timestamp, (x.reads_bytes + x.writes_bytes) / (1024. * 1024.))), 0.0, GREEN)
+                                                    .map(|x| (x.timestamp, (x.reads_bytes + x.writes_bytes) / (1024. * 1024.), x.reset)), 0.0, GREEN)
             )
                 .unwrap()
                 .label("read MBPS")
                 .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], GREEN.filled()));
-            context.draw_series(AreaSeries::new(disk_data
+            context.draw_series(area_segments(disk_data
                                                     .iter()
                                                     .filter(|x| x.hostname == server && x.disk == disk)
-                                                    .map(|x| (x.timestamp, (x.writes_bytes) / (1024. * 1024.))), 0.0, RED)
+                                                    .map(|x| (x.timestamp, (x.writes_bytes) / (1024. * 1024.), x.reset)), 0.0, RED)
             )
                 .unwrap()
                 .label("write MBPS")
@@ -629,26 +2327,26 @@ fn draw_disk(data: &Arc<Mutex<Vec<DiskGraph>>>, graph_name_addition: String) {
                 .y_desc("queue size")
                 .draw()
                 .unwrap();
-            context.draw_series(LineSeries::new(disk_data
+            context.draw_series(line_segments(disk_data
                                                     .iter()
                                                     .filter(|x| x.hostname == server && x.disk == disk)
-                                                    .map(|x| (x.timestamp, (x.reads_time / x.reads_completed) * 1000.)), GREEN)
+                                                    .map(|x| (x.timestamp, (x.reads_time / x.reads_completed) * 1000., x.reset)), GREEN)
             )
                 .unwrap()
                 .label("avg read latency")
                 .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], GREEN.filled()));
-            context.draw_series(LineSeries::new(disk_data
+            context.draw_series(line_segments(disk_data
                                                     .iter()
                                                     .filter(|x| x.hostname == server && x.disk == disk)
-                                                    .map(|x| (x.timestamp, (x.writes_time / x.writes_completed) * 1000.)), RED)
+                                                    .map(|x| (x.timestamp, (x.writes_time / x.writes_completed) * 1000., x.reset)), RED)
             )
                 .unwrap()
                 .label("avg write latency")
                 .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], RED.filled()));
-            context.draw_secondary_series(LineSeries::new(disk_data
+            context.draw_secondary_series(line_segments(disk_data
                                                               .iter()
                                                               .filter(|x| x.hostname == server && x.disk == disk)
-                                                              .map(|x| (x.timestamp, x.queue)), BLACK)
+                                                              .map(|x| (x.timestamp, x.queue, x.reset)), BLACK)
             )
                 .unwrap()
                 .label("queue size")
@@ -665,46 +2363,161 @@ fn draw_disk(data: &Arc<Mutex<Vec<DiskGraph>>>, graph_name_addition: String) {
     }
 }
 
+fn draw_network(data: &Arc<Mutex<Vec<NetworkGraph>>>, graph_name_addition: String) {
+    let network_data = data.lock().unwrap();
+
+    if network_data.iter().count() == 0 { return };
+
+    let start_time = network_data.iter().map(|x| x.timestamp).min().unwrap();
+    let end_time = network_data.iter().map(|x| x.timestamp).max().unwrap();
+    let low_value_mbps: f64 = 0.;
+    let high_value_mbps: f64 = if network_data.iter().map(|x| x.receive_bytes.max(x.transmit_bytes) / (1024. * 1024.)).fold(f64::NAN, f64::max) == 0. {
+        1.
+    } else {
+        network_data.iter().map(|x| x.receive_bytes.max(x.transmit_bytes) / (1024. * 1024.)).fold(f64::NAN, f64::max)
+    };
+    let low_value_packets: f64 = 0.;
+    let high_value_packets: f64 = if network_data.iter().map(|x| x.receive_packets.max(x.transmit_packets)).fold(f64::NAN, f64::max) == 0. {
+        1.
+    } else {
+        network_data.iter().map(|x| x.receive_packets.max(x.transmit_packets)).fold(f64::NAN, f64::max)
+    };
+
+    // the interfaces of every server, because servers do not necessarily have the same interfaces.
+    let nr_server_interfaces = network_data.iter().map(|x| (x.hostname.clone(), x.interface.clone())).unique().count();
+    // nr_server_interfaces * 2 to give MBPS and packets their graph root.
+
+    let filename = format!("network{}.png", graph_name_addition);
+    let root = BitMapBackend::new(&filename, (1200, ((nr_server_interfaces * 2) * 200).try_into().unwrap()))
+        .into_drawing_area();
+
+    let multiroot = root.split_evenly((nr_server_interfaces * 2, 1));
+    let mut multiroot_nr = 0;
+
+    for server in network_data.iter().map(|x| x.hostname.clone()).unique() {
+        for interface in network_data.iter().filter(|x| x.hostname == server).map(|x| x.interface.clone()).unique().sorted() {
+            //MBPS
+            multiroot[multiroot_nr].fill(&WHITE).unwrap();
+            let mut context = ChartBuilder::on(&multiroot[multiroot_nr])
+                .x_label_area_size(60)
+                .y_label_area_size(50)
+                .right_y_label_area_size(50)
+                .caption(format!("{} {}", &server, &interface), ("sans-serif", 20))
+                .build_cartesian_2d(start_time..end_time, low_value_mbps..high_value_mbps)
+                .unwrap();
+            context.configure_mesh()
+                .x_labels(4)
+                .x_label_formatter(&|x| x.to_rfc3339().to_string())
+                .y_desc("MB per second")
+                .draw()
+                .unwrap();
+            context.draw_series(line_segments(network_data
+                                                    .iter()
+                                                    .filter(|x| x.hostname == server && x.interface == interface)
+                                                    .map(|x| (x.timestamp, x.receive_bytes / (1024. * 1024.), x.reset)), GREEN)
+            )
+                .unwrap()
+                .label("receive MBPS")
+                .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], GREEN.filled()));
+            context.draw_series(line_segments(network_data
+                                                    .iter()
+                                                    .filter(|x| x.hostname == server && x.interface == interface)
+                                                    .map(|x| (x.timestamp, x.transmit_bytes / (1024. * 1024.), x.reset)), RED)
+            )
+                .unwrap()
+                .label("transmit MBPS")
+                .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], RED.filled()));
+            context.configure_series_labels()
+                .border_style(BLACK)
+                .background_style(WHITE.mix(0.7))
+                .position(UpperLeft)
+                .draw()
+                .unwrap();
+
+            multiroot_nr += 1;
+
+            //packets
+            multiroot[multiroot_nr].fill(&WHITE).unwrap();
+            let mut context = ChartBuilder::on(&multiroot[multiroot_nr])
+                .x_label_area_size(60)
+                .y_label_area_size(50)
+                .right_y_label_area_size(50)
+                .build_cartesian_2d(start_time..end_time, low_value_packets..high_value_packets)
+                .unwrap();
+            context.configure_mesh()
+                .x_labels(4)
+                .x_label_formatter(&|x| x.to_rfc3339().to_string())
+                .y_desc("packets per second")
+                .draw()
+                .unwrap();
+            context.draw_series(line_segments(network_data
+                                                    .iter()
+                                                    .filter(|x| x.hostname == server && x.interface == interface)
+                                                    .map(|x| (x.timestamp, x.receive_packets, x.reset)), GREEN)
+            )
+                .unwrap()
+                .label("receive packets")
+                .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], GREEN.filled()));
+            context.draw_series(line_segments(network_data
+                                                    .iter()
+                                                    .filter(|x| x.hostname == server && x.interface == interface)
+                                                    .map(|x| (x.timestamp, x.transmit_packets, x.reset)), RED)
+            )
+                .unwrap()
+                .label("transmit packets")
+                .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], RED.filled()));
+            context.configure_series_labels()
+                .border_style(BLACK)
+                .background_style(WHITE.mix(0.7))
+                .position(UpperLeft)
+                .draw()
+                .unwrap();
+
+            multiroot_nr += 1;
+        }
+    }
+}
+
 fn draw_yugabyte(yugabyte: &Arc<Mutex<Vec<YBIOGraph>>>, graph_name_addition: String) {
     let yugabyte_data = yugabyte.lock().unwrap();
 
     if yugabyte_data.iter().count() == 0 { return };
 
     let low_value_mbps: f64 = 0.;
-    let high_value_mbps: f64 = if yugabyte_data.iter().map(|x| (x.log_reader_bytes_read + x.log_bytes_logged + x.rocksdb_flush_write_bytes + x.rocksdb_compact_read_bytes + x.rocksdb_compact_write_bytes) / (1024. * 1024.)).fold(0. / 0., f64::max) == 0. {
+    let high_value_mbps: f64 = if yugabyte_data.iter().map(|x| (x.log_reader_bytes_read + x.log_bytes_logged + x.rocksdb_flush_write_bytes + x.rocksdb_compact_read_bytes + x.rocksdb_compact_write_bytes) / (1024. * 1024.)).fold(f64::NAN, f64::max) == 0. {
         1.
     } else {
-        yugabyte_data.iter().map(|x| (x.log_reader_bytes_read + x.log_bytes_logged + x.rocksdb_flush_write_bytes + x.rocksdb_compact_read_bytes + x.rocksdb_compact_write_bytes) / (1024. * 1024.)).fold(0. / 0., f64::max)
+        yugabyte_data.iter().map(|x| (x.log_reader_bytes_read + x.log_bytes_logged + x.rocksdb_flush_write_bytes + x.rocksdb_compact_read_bytes + x.rocksdb_compact_write_bytes) / (1024. * 1024.)).fold(f64::NAN, f64::max)
     };
     let low_value_iops: f64 = 0.;
-    let high_value_iops: f64 = if yugabyte_data.iter().map(|x| (x.glog_messages_info + x.glog_messages_prio + x.log_cache_disk_reads + x.log_append_latency_count + x.rocksdb_sst_read_micros_count + x.rocksdb_write_raw_block_micros_count)).fold(0. / 0., f64::max) == 0. {
+    let high_value_iops: f64 = if yugabyte_data.iter().map(|x| x.glog_messages_info + x.glog_messages_prio + x.log_cache_disk_reads + x.log_append_latency_count + x.rocksdb_sst_read_micros_count + x.rocksdb_write_raw_block_micros_count).fold(f64::NAN, f64::max) == 0. {
         1.
     } else {
-        yugabyte_data.iter().map(|x| (x.glog_messages_info + x.glog_messages_prio + x.log_cache_disk_reads + x.log_append_latency_count + x.rocksdb_sst_read_micros_count + x.rocksdb_write_raw_block_micros_count)).fold(0. / 0., f64::max)
+        yugabyte_data.iter().map(|x| x.glog_messages_info + x.glog_messages_prio + x.log_cache_disk_reads + x.log_append_latency_count + x.rocksdb_sst_read_micros_count + x.rocksdb_write_raw_block_micros_count).fold(f64::NAN, f64::max)
     };
     let low_value_latency: f64 = 0.;
     let mut latency_vec = Vec::new();
-    latency_vec.push( if yugabyte_data.iter().map(|x| (x.log_append_latency_sum / x.log_append_latency_count) / 1000.).fold(0. / 0., f64::max) == 0. {
+    latency_vec.push( if yugabyte_data.iter().map(|x| average(x.log_append_latency_sum, x.log_append_latency_count) / 1000.).fold(f64::NAN, f64::max) == 0. {
         1.
     } else {
-        yugabyte_data.iter().map(|x| (x.log_append_latency_sum / x.log_append_latency_count) / 1000.).fold(0. / 0., f64::max)
+        yugabyte_data.iter().map(|x| average(x.log_append_latency_sum, x.log_append_latency_count) / 1000.).fold(f64::NAN, f64::max)
     });
-    latency_vec.push( if yugabyte_data.iter().map(|x| (x.rocksdb_sst_read_micros_sum / x.rocksdb_sst_read_micros_count) / 1000.).fold(0. / 0., f64::max) == 0. {
+    latency_vec.push( if yugabyte_data.iter().map(|x| average(x.rocksdb_sst_read_micros_sum, x.rocksdb_sst_read_micros_count) / 1000.).fold(f64::NAN, f64::max) == 0. {
         1.
     } else {
-        yugabyte_data.iter().map(|x| (x.rocksdb_sst_read_micros_sum / x.rocksdb_sst_read_micros_count) / 1000.).fold(0. / 0., f64::max)
+        yugabyte_data.iter().map(|x| average(x.rocksdb_sst_read_micros_sum, x.rocksdb_sst_read_micros_count) / 1000.).fold(f64::NAN, f64::max)
     });
-    latency_vec.push( if yugabyte_data.iter().map(|x| (x.rocksdb_write_raw_block_micros_sum / x.rocksdb_write_raw_block_micros_count) / 1000.).fold(0. / 0., f64::max) == 0. {
+    latency_vec.push( if yugabyte_data.iter().map(|x| average(x.rocksdb_write_raw_block_micros_sum, x.rocksdb_write_raw_block_micros_count) / 1000.).fold(f64::NAN, f64::max) == 0. {
         1.
     } else {
-        yugabyte_data.iter().map(|x| (x.rocksdb_write_raw_block_micros_sum / x.rocksdb_write_raw_block_micros_count) / 1000.).fold(0. / 0., f64::max)
+        yugabyte_data.iter().map(|x| average(x.rocksdb_write_raw_block_micros_sum, x.rocksdb_write_raw_block_micros_count) / 1000.).fold(f64::NAN, f64::max)
     });
-    latency_vec.push( if yugabyte_data.iter().map(|x| (x.log_sync_latency_sum / x.log_sync_latency_count) / 1000.).fold(0. / 0., f64::max) == 0. {
+    latency_vec.push( if yugabyte_data.iter().map(|x| average(x.log_sync_latency_sum, x.log_sync_latency_count) / 1000.).fold(f64::NAN, f64::max) == 0. {
         1.
     } else {
-        yugabyte_data.iter().map(|x| (x.log_sync_latency_sum / x.log_sync_latency_count) / 1000.).fold(0. / 0., f64::max)
+        yugabyte_data.iter().map(|x| average(x.log_sync_latency_sum, x.log_sync_latency_count) / 1000.).fold(f64::NAN, f64::max)
     });
-    let high_value_latency: f64 = latency_vec.iter().cloned().fold(0. / 0., f64::max);
+    let high_value_latency: f64 = latency_vec.iter().cloned().fold(f64::NAN, f64::max);
 
     let start_time = yugabyte_data.iter().map(|x| x.timestamp).min().unwrap();
     let end_time = yugabyte_data.iter().map(|x| x.timestamp).max().unwrap();
@@ -733,42 +2546,42 @@ fn draw_yugabyte(yugabyte: &Arc<Mutex<Vec<YBIOGraph>>>, graph_name_addition: Str
             .y_desc("MB per second")
             .draw()
             .unwrap();
-        context.draw_series(AreaSeries::new(yugabyte_data
+        context.draw_series(area_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, (x.log_reader_bytes_read + x.rocksdb_compact_read_bytes + x.rocksdb_compact_write_bytes + x.rocksdb_flush_write_bytes + x.log_bytes_logged) / (1024. * 1024.))), 0.0, BLACK)
+                                                .map(|x| (x.timestamp, (x.log_reader_bytes_read + x.rocksdb_compact_read_bytes + x.rocksdb_compact_write_bytes + x.rocksdb_flush_write_bytes + x.log_bytes_logged) / (1024. * 1024.), x.reset)), 0.0, BLACK)
         )
             .unwrap()
             .label("WAL read MBPS")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], BLACK.filled()));
-        context.draw_series(AreaSeries::new(yugabyte_data
+        context.draw_series(area_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, (x.rocksdb_compact_read_bytes + x.rocksdb_compact_write_bytes + x.rocksdb_flush_write_bytes + x.log_bytes_logged) / (1024. * 1024.))), 0.0, BLUE)
+                                                .map(|x| (x.timestamp, (x.rocksdb_compact_read_bytes + x.rocksdb_compact_write_bytes + x.rocksdb_flush_write_bytes + x.log_bytes_logged) / (1024. * 1024.), x.reset)), 0.0, BLUE)
         )
             .unwrap()
             .label("RocksDB compaction read")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], BLUE.filled()));
-        context.draw_series(AreaSeries::new(yugabyte_data
+        context.draw_series(area_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, (x.rocksdb_compact_write_bytes + x.rocksdb_flush_write_bytes + x.log_bytes_logged) / (1024. * 1024.))), 0.0, YELLOW)
+                                                .map(|x| (x.timestamp, (x.rocksdb_compact_write_bytes + x.rocksdb_flush_write_bytes + x.log_bytes_logged) / (1024. * 1024.), x.reset)), 0.0, YELLOW)
         )
             .unwrap()
             .label("RocksDB compaction write")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], YELLOW.filled()));
-        context.draw_series(AreaSeries::new(yugabyte_data
+        context.draw_series(area_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, (x.rocksdb_flush_write_bytes + x.log_bytes_logged) / (1024. * 1024.))), 0.0, RED)
+                                                .map(|x| (x.timestamp, (x.rocksdb_flush_write_bytes + x.log_bytes_logged) / (1024. * 1024.), x.reset)), 0.0, RED)
         )
             .unwrap()
             .label("RocksDB flush write")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], RED.filled()));
-        context.draw_series(AreaSeries::new(yugabyte_data
+        context.draw_series(area_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, x.log_bytes_logged / (1024. * 1024.))), 0.0, GREEN)
+                                                .map(|x| (x.timestamp, x.log_bytes_logged / (1024. * 1024.), x.reset)), 0.0, GREEN)
         )
             .unwrap()
             .label("WAL log write")
@@ -796,50 +2609,50 @@ fn draw_yugabyte(yugabyte: &Arc<Mutex<Vec<YBIOGraph>>>, graph_name_addition: Str
             .y_desc("IO per second")
             .draw()
             .unwrap();
-        context.draw_series(AreaSeries::new(yugabyte_data
+        context.draw_series(area_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, (x.glog_messages_info + x.glog_messages_prio + x.log_append_latency_count + x.log_cache_disk_reads + x.rocksdb_write_raw_block_micros_count + x.rocksdb_sst_read_micros_count))), 0.0, Palette99::pick(2))
+                                                .map(|x| (x.timestamp, (x.glog_messages_info + x.glog_messages_prio + x.log_append_latency_count + x.log_cache_disk_reads + x.rocksdb_write_raw_block_micros_count + x.rocksdb_sst_read_micros_count), x.reset)), 0.0, Palette99::pick(2))
         )
             .unwrap()
             .label("glog messages info write")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], Palette99::pick(2).filled()));
-        context.draw_series(AreaSeries::new(yugabyte_data
+        context.draw_series(area_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, (x.glog_messages_prio + x.log_append_latency_count + x.log_cache_disk_reads + x.rocksdb_write_raw_block_micros_count + x.rocksdb_sst_read_micros_count))), 0.0, CYAN)
+                                                .map(|x| (x.timestamp, (x.glog_messages_prio + x.log_append_latency_count + x.log_cache_disk_reads + x.rocksdb_write_raw_block_micros_count + x.rocksdb_sst_read_micros_count), x.reset)), 0.0, CYAN)
         )
             .unwrap()
             .label("glog messages prio write")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], CYAN.filled()));
-        context.draw_series(AreaSeries::new(yugabyte_data
+        context.draw_series(area_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, (x.log_cache_disk_reads + x.rocksdb_write_raw_block_micros_count + x.rocksdb_sst_read_micros_count))), 0.0, BLACK)
+                                                .map(|x| (x.timestamp, (x.log_cache_disk_reads + x.rocksdb_write_raw_block_micros_count + x.rocksdb_sst_read_micros_count), x.reset)), 0.0, BLACK)
         )
             .unwrap()
             .label("log cache read")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], BLACK.filled()));
-        context.draw_series(AreaSeries::new(yugabyte_data
+        context.draw_series(area_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, (x.rocksdb_sst_read_micros_count + x.rocksdb_write_raw_block_micros_count + x.log_append_latency_count))), 0.0, BLUE)
+                                                .map(|x| (x.timestamp, (x.rocksdb_sst_read_micros_count + x.rocksdb_write_raw_block_micros_count + x.log_append_latency_count), x.reset)), 0.0, BLUE)
         )
             .unwrap()
             .label("RocksDB sst read")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], BLUE.filled()));
-        context.draw_series(AreaSeries::new(yugabyte_data
+        context.draw_series(area_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, (x.rocksdb_write_raw_block_micros_count + x.log_append_latency_count))), 0.0, RED)
+                                                .map(|x| (x.timestamp, (x.rocksdb_write_raw_block_micros_count + x.log_append_latency_count), x.reset)), 0.0, RED)
         )
             .unwrap()
             .label("RocksDB write raw block")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], RED.filled()));
-        context.draw_series(AreaSeries::new(yugabyte_data
+        context.draw_series(area_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, x.log_append_latency_count)), 0.0, GREEN)
+                                                .map(|x| (x.timestamp, x.log_append_latency_count, x.reset)), 0.0, GREEN)
         )
             .unwrap()
             .label("log append write")
@@ -867,34 +2680,34 @@ fn draw_yugabyte(yugabyte: &Arc<Mutex<Vec<YBIOGraph>>>, graph_name_addition: Str
             .y_desc("latency (ms)")
             .draw()
             .unwrap();
-        context.draw_series(LineSeries::new(yugabyte_data
+        context.draw_series(line_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, ((x.log_append_latency_sum / x.log_append_latency_count) / 1000.))), GREEN)
+                                                .map(|x| (x.timestamp, (average(x.log_append_latency_sum, x.log_append_latency_count) / 1000.), x.reset)), GREEN)
         )
             .unwrap()
             .label("WAL log write latency")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], GREEN.filled()));
-        context.draw_series(LineSeries::new(yugabyte_data
+        context.draw_series(line_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, ((x.rocksdb_write_raw_block_micros_sum / x.rocksdb_write_raw_block_micros_count) / 1000.))), RED)
+                                                .map(|x| (x.timestamp, (average(x.rocksdb_write_raw_block_micros_sum, x.rocksdb_write_raw_block_micros_count) / 1000.), x.reset)), RED)
         )
             .unwrap()
             .label("RocksDB write raw block latency")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], RED.filled()));
-        context.draw_series(LineSeries::new(yugabyte_data
+        context.draw_series(line_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, ((x.rocksdb_sst_read_micros_sum / x.rocksdb_sst_read_micros_count) / 1000.))), BLUE)
+                                                .map(|x| (x.timestamp, (average(x.rocksdb_sst_read_micros_sum, x.rocksdb_sst_read_micros_count) / 1000.), x.reset)), BLUE)
         )
             .unwrap()
             .label("RocksDB sst read latency")
             .legend(|(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], BLUE.filled()));
-        context.draw_series(LineSeries::new(yugabyte_data
+        context.draw_series(line_segments(yugabyte_data
                                                 .iter()
                                                 .filter(|x| x.hostname == server)
-                                                .map(|x| (x.timestamp, ((x.log_sync_latency_sum / x.log_sync_latency_count) / 1000.))), MAGENTA)
+                                                .map(|x| (x.timestamp, (average(x.log_sync_latency_sum, x.log_sync_latency_count) / 1000.), x.reset)), MAGENTA)
         )
             .unwrap()
             .label("WAL log sync latency")
@@ -910,6 +2723,83 @@ fn draw_yugabyte(yugabyte: &Arc<Mutex<Vec<YBIOGraph>>>, graph_name_addition: Str
     }
 }
 
+// the average, p50, p95 and p99 of a latency of a graph row, in microseconds.
+type Latency = fn(&YBIOGraph) -> (f64, f64, f64, f64);
+
+fn draw_yugabyte_latency(yugabyte: &Arc<Mutex<Vec<YBIOGraph>>>, graph_name_addition: String) {
+    let yugabyte_data = yugabyte.lock().unwrap();
+
+    if yugabyte_data.iter().count() == 0 { return };
+    // the percentiles are only available if the server exposes histogram buckets or summary quantiles.
+    if yugabyte_data.iter().map(|x| x.log_append_latency_p99 + x.log_sync_latency_p99 + x.rocksdb_sst_read_micros_p99 + x.rocksdb_write_raw_block_micros_p99).fold(f64::NAN, f64::max) == 0. { return };
+
+    let start_time = yugabyte_data.iter().map(|x| x.timestamp).min().unwrap();
+    let end_time = yugabyte_data.iter().map(|x| x.timestamp).max().unwrap();
+
+    let nr_servers = yugabyte_data.iter().map(|x| x.hostname.clone()).unique().count();
+
+    let filename = format!("yugabyte_latency{}.png", graph_name_addition);
+    let root = BitMapBackend::new(&filename, (1200, ((nr_servers * 4) * 200).try_into().unwrap()))
+        .into_drawing_area();
+
+    let multiroot = root.split_evenly((nr_servers * 4, 1));
+    let mut multiroot_nr = 0;
+
+    let latencies: [(&str, Latency); 4] = [
+        ("WAL log write latency (ms)", |x| (average(x.log_append_latency_sum, x.log_append_latency_count), x.log_append_latency_p50, x.log_append_latency_p95, x.log_append_latency_p99)),
+        ("WAL log sync latency (ms)", |x| (average(x.log_sync_latency_sum, x.log_sync_latency_count), x.log_sync_latency_p50, x.log_sync_latency_p95, x.log_sync_latency_p99)),
+        ("RocksDB sst read latency (ms)", |x| (average(x.rocksdb_sst_read_micros_sum, x.rocksdb_sst_read_micros_count), x.rocksdb_sst_read_micros_p50, x.rocksdb_sst_read_micros_p95, x.rocksdb_sst_read_micros_p99)),
+        ("RocksDB write raw block latency (ms)", |x| (average(x.rocksdb_write_raw_block_micros_sum, x.rocksdb_write_raw_block_micros_count), x.rocksdb_write_raw_block_micros_p50, x.rocksdb_write_raw_block_micros_p95, x.rocksdb_write_raw_block_micros_p99)),
+    ];
+    for server in yugabyte_data.iter().map(|x| x.hostname.clone()).unique() {
+        for (title, latency) in latencies {
+            let low_value: f64 = 0.;
+            let high_value: f64 = if yugabyte_data.iter().filter(|x| x.hostname == server).map(|x| (latency(x).0 / 1000.).max(latency(x).3 / 1000.)).fold(f64::NAN, f64::max) == 0. {
+                1.
+            } else {
+                yugabyte_data.iter().filter(|x| x.hostname == server).map(|x| (latency(x).0 / 1000.).max(latency(x).3 / 1000.)).fold(f64::NAN, f64::max)
+            };
+            multiroot[multiroot_nr].fill(&WHITE).unwrap();
+            let mut context = ChartBuilder::on(&multiroot[multiroot_nr])
+                .x_label_area_size(60)
+                .y_label_area_size(50)
+                .right_y_label_area_size(50)
+                .caption(format!("{} {}", &server, title), ("sans-serif", 20))
+                .build_cartesian_2d(start_time..end_time, low_value..high_value)
+                .unwrap();
+            context.configure_mesh()
+                .x_labels(4)
+                .x_label_formatter(&|x| x.to_rfc3339().to_string())
+                .y_desc("latency (ms)")
+                .draw()
+                .unwrap();
+            for (label, color, value) in [
+                ("average", BLACK, (|x| x.0) as fn((f64, f64, f64, f64)) -> f64),
+                ("p50", GREEN, |x| x.1),
+                ("p95", BLUE, |x| x.2),
+                ("p99", RED, |x| x.3),
+            ] {
+                context.draw_series(line_segments(yugabyte_data
+                                                        .iter()
+                                                        .filter(|x| x.hostname == server)
+                                                        .map(|x| (x.timestamp, value(latency(x)) / 1000., x.reset)), color)
+                )
+                    .unwrap()
+                    .label(label)
+                    .legend(move |(x, y)| Rectangle::new([(x - 3, y - 3), (x + 3, y + 3)], color.filled()));
+            }
+            context.configure_series_labels()
+                .border_style(BLACK)
+                .background_style(WHITE.mix(0.7))
+                .position(UpperLeft)
+                .draw()
+                .unwrap();
+
+            multiroot_nr += 1;
+        }
+    }
+}
+
 
 /*
 